@@ -0,0 +1,302 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error("Failed to read the archive directory.")]
+    ReadFailed,
+
+    #[error("Failed to write the archive directory.")]
+    WriteFailed,
+
+    #[error("Failed to pack the project: {0}.")]
+    PackFailed(String),
+
+    #[error("Failed to unpack the project: {0}.")]
+    UnpackFailed(String),
+
+    #[error("Project '{0}' is already archived.")]
+    AlreadyArchived(String),
+
+    #[error("Project '{0}' not found in the archive.")]
+    EntryNotFound(String),
+
+    #[error("Several archived projects are named '{0}'. Use the name with the root, e.g. '{1}'.")]
+    AmbiguousName(String, String),
+}
+
+/// Compression of archived projects.
+#[derive(Deserialize, Serialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    #[default]
+    #[serde(rename = "tar.zst")]
+    #[value(name = "tar.zst")]
+    TarZst,
+
+    #[serde(rename = "tar.gz")]
+    #[value(name = "tar.gz")]
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::TarZst => "tar.zst",
+            Self::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// An archived project. The project is packed into `<file>.<extension>` and this
+/// metadata is stored next to it in `<file>.json`, where `<file>` is made of the
+/// root and the name of the project.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArchiveEntry {
+    /// Path of the project relative to its root, e.g. `api` or `clients/acme/api`.
+    pub name: String,
+
+    /// Name of the root the project was in.
+    pub root: String,
+
+    /// Where the project was before it was archived.
+    pub path: PathBuf,
+
+    pub format: ArchiveFormat,
+
+    /// Time of archiving as seconds since the Unix epoch.
+    pub archived_at: u64,
+
+    /// Size of the archive file in bytes.
+    #[serde(skip)]
+    pub size: u64,
+
+    /// Name of the files of the entry without extensions.
+    #[serde(skip)]
+    file: String,
+}
+
+impl ArchiveEntry {
+    /// Name of the project prefixed with the name of its root, e.g. `work/api`.
+    pub fn qualified_name(&self) -> String {
+        format!("{}/{}", self.root, self.name)
+    }
+}
+
+/// Name of the archive files for a project. Slashes are escaped, so that projects
+/// from groups and from different roots never share files.
+fn file_name(root: &str, name: &str) -> String {
+    format!("{root}/{name}")
+        .replace('%', "%25")
+        .replace('/', "%2F")
+}
+
+pub struct Archives {
+    dir: PathBuf,
+}
+
+impl Archives {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn archive_file(&self, name: &str, format: ArchiveFormat) -> PathBuf {
+        self.dir.join(format!("{name}.{}", format.extension()))
+    }
+
+    fn entry_file(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Packs the project at `path`, which is `name` relative to the `root`, into the
+    /// archive directory. Directories with names from `exclude` are left out.
+    /// The project itself is not removed.
+    pub fn pack(
+        &self,
+        root: &str,
+        name: &str,
+        path: &Path,
+        format: ArchiveFormat,
+        exclude: &[String],
+        archived_at: u64,
+    ) -> Result<ArchiveEntry, ArchiveError> {
+        let file = file_name(root, name);
+        if self.entry_file(&file).exists()
+            || self
+                .entries()?
+                .iter()
+                .any(|entry| entry.root == root && entry.name == name)
+        {
+            return Err(ArchiveError::AlreadyArchived(name.to_string()));
+        }
+        fs::create_dir_all(&self.dir).map_err(|_| ArchiveError::WriteFailed)?;
+
+        let archive_file = self.archive_file(&file, format);
+        let size = match write_archive(&archive_file, path, format, exclude) {
+            Ok(size) => size,
+            Err(e) => {
+                let _ = fs::remove_file(&archive_file);
+                return Err(ArchiveError::PackFailed(e.to_string()));
+            }
+        };
+
+        let entry = ArchiveEntry {
+            name: name.to_string(),
+            root: root.to_string(),
+            path: path.to_path_buf(),
+            format,
+            archived_at,
+            size,
+            file,
+        };
+        let content = serde_json::to_string(&entry).map_err(|_| ArchiveError::WriteFailed)?;
+        if fs::write(self.entry_file(&entry.file), content).is_err() {
+            let _ = fs::remove_file(&archive_file);
+            return Err(ArchiveError::WriteFailed);
+        }
+        Ok(entry)
+    }
+
+    /// Unpacks the project into `target`, which must not exist yet, and removes it from the archive.
+    pub fn unpack(&self, entry: &ArchiveEntry, target: &Path) -> Result<(), ArchiveError> {
+        let archive_file = self.archive_file(&entry.file, entry.format);
+        if let Err(e) = read_archive(&archive_file, target, entry.format) {
+            let _ = fs::remove_dir_all(target);
+            return Err(ArchiveError::UnpackFailed(e.to_string()));
+        }
+        self.remove(entry)
+    }
+
+    /// Deletes the archived project.
+    pub fn remove(&self, entry: &ArchiveEntry) -> Result<(), ArchiveError> {
+        fs::remove_file(self.archive_file(&entry.file, entry.format))
+            .and_then(|_| fs::remove_file(self.entry_file(&entry.file)))
+            .map_err(|_| ArchiveError::WriteFailed)
+    }
+
+    /// All archived projects ordered by name. Files without valid metadata are skipped.
+    pub fn entries(&self) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for dir_entry in fs::read_dir(&self.dir).map_err(|_| ArchiveError::ReadFailed)? {
+            let path = dir_entry.map_err(|_| ArchiveError::ReadFailed)?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(mut entry) = serde_json::from_str::<ArchiveEntry>(&content) else {
+                continue;
+            };
+            // Entries of older versions are named after the project directory only.
+            let Some(file) = path.file_stem() else {
+                continue;
+            };
+            entry.file = file.to_string_lossy().into_owned();
+            let Ok(metadata) = fs::metadata(self.archive_file(&entry.file, entry.format)) else {
+                continue;
+            };
+            entry.size = metadata.len();
+            entries.push(entry);
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Finds an archived project by its name or, if several roots have a project
+    /// with this name, by the name prefixed with the root, e.g. `work/api`.
+    pub fn find(&self, name: &str) -> Result<ArchiveEntry, ArchiveError> {
+        let entries = self.entries()?;
+        if let Some(entry) = entries.iter().find(|entry| entry.qualified_name() == name) {
+            return Ok(entry.clone());
+        }
+
+        let mut found = entries.into_iter().filter(|entry| entry.name == name);
+        match (found.next(), found.next()) {
+            (Some(entry), None) => Ok(entry),
+            (Some(entry), Some(_)) => Err(ArchiveError::AmbiguousName(
+                name.to_string(),
+                entry.qualified_name(),
+            )),
+            (None, _) => Err(ArchiveError::EntryNotFound(name.to_string())),
+        }
+    }
+}
+
+/// Writes the archive and returns its size in bytes.
+fn write_archive(
+    archive_file: &Path,
+    source: &Path,
+    format: ArchiveFormat,
+    exclude: &[String],
+) -> io::Result<u64> {
+    let file = File::create(archive_file)?;
+    let file = match format {
+        ArchiveFormat::TarZst => {
+            let encoder = zstd::Encoder::new(file, 0)?;
+            write_tar(encoder, source, exclude)?.finish()?
+        }
+        ArchiveFormat::TarGz => {
+            let encoder = GzEncoder::new(file, Compression::default());
+            write_tar(encoder, source, exclude)?.finish()?
+        }
+    };
+    file.sync_all()?;
+    Ok(file.metadata()?.len())
+}
+
+fn write_tar<W: Write>(writer: W, source: &Path, exclude: &[String]) -> io::Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+    append_dir(&mut builder, source, Path::new(""), exclude)?;
+    builder.into_inner()
+}
+
+fn append_dir<W: Write>(
+    builder: &mut tar::Builder<W>,
+    dir: &Path,
+    prefix: &Path,
+    exclude: &[String],
+) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        let path = entry.path();
+        let archive_path = prefix.join(&name);
+
+        if entry.file_type()?.is_dir() {
+            if exclude.iter().any(|excluded| name == excluded.as_str()) {
+                continue;
+            }
+            builder.append_dir(&archive_path, &path)?;
+            append_dir(builder, &path, &archive_path, exclude)?;
+        } else {
+            builder.append_path_with_name(&path, &archive_path)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_archive(archive_file: &Path, target: &Path, format: ArchiveFormat) -> io::Result<()> {
+    let file = File::open(archive_file)?;
+    let decoder: Box<dyn Read> = match format {
+        ArchiveFormat::TarZst => Box::new(zstd::Decoder::new(file)?),
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+    };
+    fs::create_dir_all(target)?;
+    tar::Archive::new(decoder).unpack(target)
+}
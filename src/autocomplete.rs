@@ -1,21 +1,63 @@
-use crate::{config::Config, terminal::ask_dialog};
+use std::{
+    collections::HashMap,
+    io::{self, IsTerminal},
+};
+
+use crate::{config::Config, fuzzy, terminal::ask_dialog};
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum CompletionResult {
     Found,
+    /// The only word that starts with the input, or the only nested project whose
+    /// last segment is the input.
+    FoundUnique(String),
     FoundSimilar(String),
     Nothing,
 }
 
-pub fn autocomplete(word: &str, words_list: Vec<&str>, config: &Config) -> Option<String> {
-    let suggested = suggest_completion(word, words_list.clone());
+/// How a command treats a project name that is not an exact match.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MatchPolicy {
+    /// Unique matches are accepted without asking if `always_accept` is enabled.
+    Lenient,
+    /// Anything but an exact name is confirmed. Used by commands that remove or move projects.
+    Strict,
+}
+
+/// Whether the user has to confirm the suggestion before a command uses it.
+/// Fuzzy matches are always confirmed, whatever `always_accept` says.
+pub fn needs_confirmation(
+    result: &CompletionResult,
+    always_accept: bool,
+    policy: MatchPolicy,
+) -> bool {
+    match result {
+        CompletionResult::Found | CompletionResult::Nothing => false,
+        CompletionResult::FoundUnique(_) => policy == MatchPolicy::Strict || !always_accept,
+        CompletionResult::FoundSimilar(_) => true,
+    }
+}
+
+pub fn autocomplete(
+    word: &str,
+    words_list: Vec<&str>,
+    scores: &HashMap<String, f64>,
+    config: &Config,
+    policy: MatchPolicy,
+) -> Option<String> {
+    let suggested = suggest_completion(word, words_list, scores);
+    let confirm = needs_confirmation(&suggested, config.autocomplete.always_accept, policy);
 
     match suggested {
         CompletionResult::Found => Some(word.to_string()),
-        CompletionResult::FoundSimilar(name) => {
-            if config.autocomplete.always_accept {
+        CompletionResult::FoundUnique(name) | CompletionResult::FoundSimilar(name) => {
+            if !confirm {
                 return Some(name);
             }
+            // Without a terminal there is nobody to confirm the guess.
+            if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+                return None;
+            }
             let answer = ask_dialog(&format!("Did you mean '{name}'?"), true);
             if answer { Some(name) } else { None }
         }
@@ -23,14 +65,38 @@ pub fn autocomplete(word: &str, words_list: Vec<&str>, config: &Config) -> Optio
     }
 }
 
-pub fn suggest_completion(word: &str, words_list: Vec<&str>) -> CompletionResult {
+/// Suggests a completion, preferring words with higher `scores` among equal matches.
+pub fn suggest_completion(
+    word: &str,
+    words_list: Vec<&str>,
+    scores: &HashMap<String, f64>,
+) -> CompletionResult {
     if words_list.contains(&word) {
         return CompletionResult::Found;
     }
 
-    if let Some(similiar) = words_list.iter().find(|entry| entry.starts_with(word)) {
-        CompletionResult::FoundSimilar(similiar.to_string())
-    } else {
-        CompletionResult::Nothing
+    // Nested projects like `clients/acme/api` can be referred to by their last segment.
+    let by_segment = words_list
+        .iter()
+        .filter(|name| name.rsplit('/').next() == Some(word));
+    if let Some(name) = unique(by_segment) {
+        return CompletionResult::FoundUnique(name.to_string());
+    }
+
+    let by_prefix = words_list.iter().filter(|name| name.starts_with(word));
+    if let Some(name) = unique(by_prefix) {
+        return CompletionResult::FoundUnique(name.to_string());
+    }
+
+    match fuzzy::rank(word, &words_list, scores).into_iter().next() {
+        Some(best) => CompletionResult::FoundSimilar(best.name),
+        None => CompletionResult::Nothing,
+    }
+}
+
+fn unique<T>(mut items: impl Iterator<Item = T>) -> Option<T> {
+    match (items.next(), items.next()) {
+        (Some(item), None) => Some(item),
+        _ => None,
     }
 }
@@ -1,5 +1,11 @@
-﻿use clap::{ArgAction, Args, Parser, Subcommand};
-use std::env;
+﻿use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use clap_complete::ArgValueCandidates;
+use std::{env, path::PathBuf};
+
+use crate::{archive::ArchiveFormat, completions, detect::Language, output::OutputFormat};
+
+/// Environment variable that makes Kanri print completions instead of running a command.
+pub const COMPLETE_VAR: &str = "COMPLETE";
 
 /// Yet another manager for your projects.
 #[derive(Parser)]
@@ -18,6 +24,10 @@ pub struct Cli {
     /// Print the version of Kanri.
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub version: bool,
+
+    /// Format of the output. `json` and `yaml` are supported by listing and info commands.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
 }
 
 #[derive(Subcommand)]
@@ -43,6 +53,55 @@ pub enum Commands {
     #[command(alias = "rm")]
     Remove(RemoveArgs),
 
+    /// Add or remove tags of a project.
+    Tag {
+        #[command(subcommand)]
+        command: TagCommands,
+    },
+
+    /// Set or show the description of a project.
+    Describe(DescribeArgs),
+
+    /// Pin a project to the top of the list.
+    Pin(PinArgs),
+
+    /// Pack project into a compressed archive and remove it from the library.
+    Archive(ArchiveArgs),
+
+    /// Restore an archived project.
+    Unarchive(UnarchiveArgs),
+
+    /// List recently opened projects.
+    Recent(RecentArgs),
+
+    /// Show the Git status of every project.
+    Status(StatusArgs),
+
+    /// Fetch or pull every Git project.
+    Sync(SyncArgs),
+
+    /// Write Git remotes of your projects into a manifest for `clone --manifest`.
+    ExportManifest(ExportManifestArgs),
+
+    /// Print the path to the project.
+    Path(PathArgs),
+
+    /// Change directory to the project (requires shell integration).
+    #[command(hide = true)]
+    Cd(PathArgs),
+
+    /// Print the shell integration script.
+    ShellInit(ShellInitArgs),
+
+    /// Print the script that enables completions for the shell.
+    Completions(CompletionsArgs),
+
+    /// Restore or purge removed projects.
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+
     /// Manage your templates.
     Templates {
         #[command(subcommand)]
@@ -65,12 +124,33 @@ pub struct NewArgs {
     pub name: Option<String>,
 
     /// Template to use for a new project.
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(completions::template_names))]
     pub template: Option<String>,
 
     /// Hide the output of running commands.
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub quiet: bool,
+
+    /// Value for a template variable. Can be used multiple times.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub vars: Vec<(String, String)>,
+
+    /// Print what would be done without changing anything.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+
+    /// Root to create the project in.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+fn parse_key_value(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{value}'")),
+    }
 }
 
 #[derive(Args)]
@@ -85,16 +165,82 @@ pub struct CloneArgs {
     /// Branch to clone.
     #[arg(short, long)]
     pub branch: Option<String>,
+
+    /// Root to clone the repository into.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+
+    /// Create a shallow clone with this many commits.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub depth: Option<u32>,
+
+    /// Clone submodules too.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub recurse_submodules: bool,
+
+    /// Check out only these directories, separated by commas.
+    #[arg(
+        long,
+        value_name = "PATHS",
+        value_delimiter = ',',
+        conflicts_with = "bare"
+    )]
+    pub sparse: Vec<String>,
+
+    /// Clone only the history of one branch.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub single_branch: bool,
+
+    /// Clone a bare repository without a working tree.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub bare: bool,
+
+    /// Template to apply to the cloned project, e.g. to install dependencies.
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["bare", "manifest"],
+        add = ArgValueCandidates::new(completions::template_names)
+    )]
+    pub template: Option<String>,
+
+    /// Value for a template variable. Can be used multiple times.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value, requires = "template")]
+    pub vars: Vec<(String, String)>,
+
+    /// Hide the output of Git and template commands.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub quiet: bool,
+
+    /// Clone every repository listed in this manifest file that is not cloned yet.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["remote", "name", "branch"])]
+    pub manifest: Option<PathBuf>,
+
+    /// Number of repositories cloned at the same time with `--manifest`.
+    #[arg(short, long, requires = "manifest", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
 }
 
 #[derive(Args)]
 pub struct OpenArgs {
-    /// Name of the project to open.
+    /// Name of the project to open. Use `-`, `-2`, `-3`... to open recent projects.
+    #[arg(
+        allow_negative_numbers = true,
+        add = ArgValueCandidates::new(completions::project_names)
+    )]
     pub name: Option<String>,
 
     /// Open shell in this project.
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub shell: bool,
+
+    /// Use this editor profile, or shell profile with `--shell`, instead of the configured one.
+    #[arg(long, value_name = "PROFILE", add = ArgValueCandidates::new(completions::profile_names))]
+    pub with: Option<String>,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
 }
 
 #[derive(Args)]
@@ -102,25 +248,382 @@ pub struct ListArgs {
     /// Display list without styling
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub pure: bool,
+
+    /// Show projects only from this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+
+    /// Order of projects in the list.
+    #[arg(long, value_enum)]
+    pub sort: Option<ListSort>,
+
+    /// Reverse the order of projects.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub reverse: bool,
+
+    /// Show only projects with names matching this glob, e.g. `api-*`.
+    #[arg(long, value_name = "GLOB")]
+    pub filter: Option<String>,
+
+    /// Show a table with details of each project.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub long: bool,
+
+    /// Show archived projects instead.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub archived: bool,
+
+    /// Show only projects with this tag. Can be used multiple times.
+    #[arg(long, add = ArgValueCandidates::new(completions::tag_names))]
+    pub tag: Vec<String>,
+
+    /// Show only pinned projects.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub pinned: bool,
+
+    /// Show only projects written in this language. Can be used multiple times.
+    #[arg(long, value_enum)]
+    pub lang: Vec<Language>,
+
+    /// Show only projects whose description contains this text.
+    #[arg(long, value_name = "TEXT")]
+    pub description: Option<String>,
+
+    /// Show only projects created with Kanri within this time, e.g. `30d`.
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub created_within: Option<u64>,
+
+    /// Show only projects opened within this time, e.g. `2w`.
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub opened_within: Option<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListSort {
+    /// Sort by name.
+    Name,
+
+    /// Sort by how often and how recently projects were opened.
+    Frecency,
+
+    /// Sort by the time of the last change, newest first.
+    Modified,
+
+    /// Sort by the time of creation, newest first.
+    Created,
+
+    /// Sort by the time of the last opening, most recent first.
+    Opened,
+
+    /// Sort by the size on disk, largest first.
+    Size,
+}
+
+#[derive(Args)]
+pub struct StatusArgs {
+    /// Display table without styling
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub pure: bool,
+
+    /// Show only projects with uncommitted changes or untracked files.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub dirty_only: bool,
+
+    /// Show projects only from this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+
+    /// Number of repositories checked at the same time.
+    #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+}
+
+#[derive(Args)]
+pub struct SyncArgs {
+    /// Run `git pull --ff-only` instead of `git fetch`.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub pull: bool,
+
+    /// Sync repositories with uncommitted changes too.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub force: bool,
+
+    /// Sync projects only from this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+
+    /// Number of repositories synced at the same time.
+    #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+}
+
+#[derive(Args)]
+pub struct ExportManifestArgs {
+    /// File to write the manifest to. Printed to stdout if not given.
+    pub file: Option<PathBuf>,
+
+    /// Export projects only from this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+#[derive(Args)]
+pub struct RecentArgs {
+    /// Display list without styling
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub pure: bool,
+
+    /// Maximum number of projects to display.
+    #[arg(short = 'n', long)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Args)]
+pub struct PathArgs {
+    /// Name of the project. Use `-`, `-2`, `-3`... for recent projects.
+    #[arg(
+        allow_negative_numbers = true,
+        add = ArgValueCandidates::new(completions::project_names)
+    )]
+    pub name: Option<String>,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+
+    /// Record the visit in the history of recent projects.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub record: bool,
+}
+
+#[derive(Args)]
+pub struct ShellInitArgs {
+    /// Shell to generate the integration script for.
+    #[arg(value_enum)]
+    pub shell: InitShell,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InitShell {
+    Bash,
+    Zsh,
+    Fish,
+    Pwsh,
+}
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for.
+    #[arg(value_enum)]
+    pub shell: CompletionShell,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+    Powershell,
 }
 
 #[derive(Args)]
 pub struct RenameArgs {
     /// Old project name.
+    #[arg(add = ArgValueCandidates::new(completions::project_names))]
     pub old_name: Option<String>,
 
     /// New project name.
     pub new_name: Option<String>,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
 }
 
 #[derive(Args)]
 pub struct RemoveArgs {
     /// Name of the project to remove.
+    #[arg(
+        allow_negative_numbers = true,
+        add = ArgValueCandidates::new(completions::project_names)
+    )]
     pub name: Option<String>,
 
     /// Force remove without confirmation
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub force: bool,
+
+    /// Delete the project instead of moving it to the trash.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub permanent: bool,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum TagCommands {
+    /// Add tags to a project.
+    Add(TagArgs),
+
+    /// Remove tags from a project.
+    Remove(TagArgs),
+
+    /// List all tags with the number of projects.
+    List(TagListArgs),
+}
+
+#[derive(Args)]
+pub struct TagArgs {
+    /// Name of the project.
+    #[arg(
+        allow_negative_numbers = true,
+        add = ArgValueCandidates::new(completions::project_names)
+    )]
+    pub name: String,
+
+    /// Tags to add or remove.
+    #[arg(required = true, add = ArgValueCandidates::new(completions::tag_names))]
+    pub tags: Vec<String>,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TagListArgs {
+    /// Display list without styling
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub pure: bool,
+}
+
+#[derive(Args)]
+pub struct DescribeArgs {
+    /// Name of the project.
+    #[arg(
+        allow_negative_numbers = true,
+        add = ArgValueCandidates::new(completions::project_names)
+    )]
+    pub name: Option<String>,
+
+    /// New description. Prints the current one if omitted.
+    pub description: Option<String>,
+
+    /// Remove the description.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "description")]
+    pub clear: bool,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+#[derive(Args)]
+pub struct PinArgs {
+    /// Name of the project.
+    #[arg(
+        allow_negative_numbers = true,
+        add = ArgValueCandidates::new(completions::project_names)
+    )]
+    pub name: Option<String>,
+
+    /// Unpin the project instead.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub unpin: bool,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ArchiveArgs {
+    /// Name of the project to archive.
+    #[arg(
+        allow_negative_numbers = true,
+        add = ArgValueCandidates::new(completions::project_names)
+    )]
+    pub name: Option<String>,
+
+    /// Compression of the archive. Defaults to the format from the configuration file.
+    #[arg(long, value_enum)]
+    pub compression: Option<ArchiveFormat>,
+
+    /// Leave out build artifacts like `target` and `node_modules`.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub exclude_artifacts: bool,
+
+    /// Look for the project only in this root.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+#[derive(Args)]
+pub struct UnarchiveArgs {
+    /// Name of the archived project.
+    #[arg(add = ArgValueCandidates::new(completions::archived_names))]
+    pub name: Option<String>,
+
+    /// Root to restore the project into, if it differs from the original one.
+    #[arg(long, add = ArgValueCandidates::new(completions::root_names))]
+    pub root: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommands {
+    /// List removed projects.
+    List(TrashListArgs),
+
+    /// Move a removed project back to where it was.
+    Restore(TrashRestoreArgs),
+
+    /// Permanently delete removed projects.
+    Purge(TrashPurgeArgs),
+}
+
+#[derive(Args)]
+pub struct TrashListArgs {
+    /// Display list without styling
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub pure: bool,
+}
+
+#[derive(Args)]
+pub struct TrashRestoreArgs {
+    /// Name of the removed project.
+    #[arg(add = ArgValueCandidates::new(completions::trashed_names))]
+    pub name: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TrashPurgeArgs {
+    /// Only delete projects removed earlier than this, e.g. `30d`, `12h` or `2w`.
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub older_than: Option<u64>,
+
+    /// Purge without confirmation
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub force: bool,
+}
+
+/// Parses an age like `30d` into seconds. Supported units are `s`, `m`, `h`, `d` and `w`.
+fn parse_age(value: &str) -> Result<u64, String> {
+    let invalid = || format!("expected a number with a unit (s, m, h, d, w), got '{value}'");
+    let value = value.trim();
+    let unit = value.chars().last().ok_or_else(invalid)?;
+    let seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        'w' => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let amount: u64 = value[..value.len() - 1].parse().map_err(|_| invalid())?;
+    amount.checked_mul(seconds).ok_or_else(invalid)
 }
 
 #[derive(Subcommand)]
@@ -157,6 +660,7 @@ pub struct TemplatesListArgs {
 #[derive(Args)]
 pub struct TemplatesInfoArgs {
     /// Name of the template.
+    #[arg(add = ArgValueCandidates::new(completions::template_names))]
     pub name: Option<String>,
 
     /// Display list without styling
@@ -167,6 +671,7 @@ pub struct TemplatesInfoArgs {
 #[derive(Args)]
 pub struct TemplatesRemoveArgs {
     /// Name of the template to remove.
+    #[arg(add = ArgValueCandidates::new(completions::template_names))]
     pub name: Option<String>,
 }
 
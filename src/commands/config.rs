@@ -1,35 +1,63 @@
+use std::path::Path;
+
 use anyhow::{Result, anyhow, bail};
 
 use crate::{
-    config::Config,
+    config::{Config, ConfigError},
+    output::{self, PathInfo},
     platform,
     program::{LaunchOptions, launch_program},
-    terminal::{ask_dialog, print_done},
+    terminal::{ask_dialog, print_done, print_warning},
 };
 
 pub fn handle_path() -> Result<()> {
-    println!("{}", platform::config_file().display());
+    let path = platform::config_file();
+    if output::is_structured() {
+        output::print(&PathInfo::new(&path))?;
+    } else {
+        println!("{}", path.display());
+    }
     Ok(())
 }
 
 pub fn handle_edit() -> Result<()> {
     let path = platform::config_file();
-    let config = Config::load(&path)?;
-    let editor = &config.editor.program;
-    if editor.is_empty() {
+    // A broken configuration file must still be editable, so the editor from
+    // `$VISUAL` or `$EDITOR` is used when the configured one can not be.
+    let problem = match Config::load(&path) {
+        Ok(config) => match config.default_editor() {
+            Ok(_) => return edit_file(&config, &path),
+            Err(e) => e,
+        },
+        Err(ConfigError::FileNotFound) => return Err(ConfigError::FileNotFound.into()),
+        Err(e) => e,
+    };
+    let config = Config::default();
+    print_warning(&format!(
+        "{problem} Opening it with '{}'.",
+        config.default_editor()?.program
+    ));
+    edit_file(&config, &path)
+}
+
+/// Opens the file in the default editor and waits for it to exit.
+pub(crate) fn edit_file(config: &Config, path: &Path) -> Result<()> {
+    let editor = config.default_editor()?;
+    if editor.program.is_empty() {
         bail!("Editor program name is not set in the configuration file.");
     }
 
-    let mut editor_args = config.editor.args.clone();
-    editor_args.push(path.to_str().unwrap().to_string());
+    let mut editor_args = editor.args.clone();
+    editor_args.push(path.to_string_lossy().into_owned());
 
     let launch_options = LaunchOptions {
-        program: editor.to_string(),
+        program: editor.program.clone(),
         args: editor_args,
         fork_mode: false,
         quiet: false,
         cwd: None,
         env: None,
+        timeout: None,
     };
 
     launch_program(launch_options).map_err(|e| anyhow!(e.to_string()))
@@ -0,0 +1,391 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Result, anyhow};
+use colored::{ColoredString, Colorize};
+use globset::Glob;
+
+use crate::{
+    archive::Archives,
+    cli::{ListArgs, ListSort},
+    commands::{
+        metadata::read_metadata,
+        root::{load_history, load_library},
+    },
+    config::{Config, ListColumn},
+    git::{self, GitStatus},
+    history,
+    library::Project,
+    metadata::{MetadataStore, ProjectMetadata},
+    output::{self, ArchivedProjectInfo, GitInfo, ProjectInfo},
+    platform,
+    terminal::{format_elapsed, format_size, print_title},
+};
+
+/// A project with everything `list` may show or sort by.
+/// Values that are expensive to get are only filled in when needed.
+struct ListEntry<'a> {
+    project: &'a Project,
+    metadata: ProjectMetadata,
+    created: Option<u64>,
+    opened: Option<u64>,
+    modified: Option<u64>,
+    size: Option<u64>,
+    git: Option<GitStatus>,
+}
+
+fn list_archived(config: &Config, pure: bool) -> Result<()> {
+    let entries = Archives::new(config.archive.directory.clone()).entries()?;
+    if output::is_structured() {
+        let entries: Vec<ArchivedProjectInfo> = entries
+            .into_iter()
+            .map(|entry| ArchivedProjectInfo {
+                name: entry.name,
+                path: entry.path,
+                root: entry.root,
+                format: entry.format.extension().to_string(),
+                size: entry.size,
+                archived_at: entry.archived_at,
+            })
+            .collect();
+        output::print(&entries)?;
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No archived projects.");
+        return Ok(());
+    }
+
+    if !pure {
+        print_title("Archived projects");
+    }
+    let now = history::now();
+    for entry in entries.iter() {
+        if pure {
+            println!("{}", entry.name);
+        } else {
+            let details = format!(
+                "[{}] ({}, archived {})",
+                entry.root,
+                format_size(entry.size),
+                format_elapsed(entry.archived_at, now)
+            );
+            println!(" {} {}", entry.name, details.dimmed());
+        }
+    }
+    Ok(())
+}
+
+fn format_time(timestamp: Option<u64>, now: u64) -> String {
+    timestamp.map_or_else(|| String::from("-"), |t| format_elapsed(t, now))
+}
+
+fn format_git(status: &Option<GitStatus>) -> String {
+    match status {
+        Some(status) => {
+            let branch = status.branch.as_deref().unwrap_or("(detached)");
+            if status.is_dirty() {
+                format!("{branch}*")
+            } else {
+                branch.to_string()
+            }
+        }
+        None => String::from("-"),
+    }
+}
+
+fn join_or_dash<T: ToString>(items: &[T]) -> String {
+    if items.is_empty() {
+        return String::from("-");
+    }
+    let items: Vec<String> = items.iter().map(T::to_string).collect();
+    items.join(", ")
+}
+
+fn cell(column: ListColumn, entry: &ListEntry, now: u64) -> String {
+    match column {
+        ListColumn::Name => entry.project.get_name().to_string(),
+        ListColumn::Root => entry.project.get_root().to_string(),
+        ListColumn::Path => entry.project.get_path().to_string(),
+        ListColumn::Modified => format_time(entry.modified, now),
+        ListColumn::Created => format_time(entry.created, now),
+        ListColumn::Opened => format_time(entry.opened, now),
+        ListColumn::Size => entry.size.map_or_else(|| String::from("-"), format_size),
+        ListColumn::Language => join_or_dash(&entry.project.project_type().languages),
+        ListColumn::Build => join_or_dash(&entry.project.project_type().build_systems),
+        ListColumn::Git => format_git(&entry.git),
+        ListColumn::Tags => entry.metadata.tags.join(", "),
+        ListColumn::Description => entry.metadata.description.clone(),
+    }
+}
+
+fn paint(column: ListColumn, entry: &ListEntry, text: String) -> ColoredString {
+    match column {
+        ListColumn::Name if entry.metadata.pinned => text.yellow().bold(),
+        ListColumn::Name => text.normal(),
+        ListColumn::Git => match &entry.git {
+            Some(status) if status.is_dirty() => text.yellow(),
+            Some(_) => text.green(),
+            None => text.dimmed(),
+        },
+        ListColumn::Tags => text.cyan(),
+        _ => text.dimmed(),
+    }
+}
+
+fn print_table(entries: &[ListEntry], columns: &[ListColumn], pure: bool, now: u64) {
+    let rows: Vec<Vec<String>> = entries
+        .iter()
+        .map(|entry| columns.iter().map(|c| cell(*c, entry, now)).collect())
+        .collect();
+
+    if pure {
+        for row in rows {
+            println!("{}", row.join("\t"));
+        }
+        return;
+    }
+
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{column:?}").to_uppercase())
+        .collect();
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|idx| {
+            rows.iter()
+                .map(|row| row[idx].chars().count())
+                .chain([headers[idx].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let header: Vec<String> = headers
+        .iter()
+        .zip(widths.iter())
+        .map(|(header, width)| format!("{header:width$}"))
+        .collect();
+    println!(" {}", header.join("  ").trim_end().bold());
+
+    for (entry, row) in entries.iter().zip(rows) {
+        let cells: Vec<String> = row
+            .into_iter()
+            .zip(columns.iter().zip(widths.iter()))
+            .enumerate()
+            .map(|(idx, (text, (column, width)))| {
+                let text = if idx + 1 == columns.len() {
+                    text
+                } else {
+                    format!("{text:width$}")
+                };
+                paint(*column, entry, text).to_string()
+            })
+            .collect();
+        println!(" {}", cells.join("  "));
+    }
+}
+
+pub fn handle_list(args: ListArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    if args.archived {
+        return list_archived(&config, args.pure);
+    }
+
+    let projects = load_library(&config, args.root.as_deref())?;
+    let structured = output::is_structured();
+    if projects.is_empty() && !structured {
+        println!("No projects found.");
+        return Ok(());
+    }
+
+    let filter = args
+        .filter
+        .as_deref()
+        .map(|pattern| {
+            Glob::new(pattern)
+                .map(|glob| glob.compile_matcher())
+                .map_err(|e| anyhow!("Invalid filter '{pattern}': {}", e.kind()))
+        })
+        .transpose()?;
+
+    let history = load_history(&config)?;
+    let recent = history.recent(1).unwrap_or_default();
+    let show_roots = projects.get_roots().len() > 1;
+    let store = MetadataStore::load(platform::metadata_file())?;
+    let now = history::now();
+    let within = |timestamp: Option<u64>, age: Option<u64>| match age {
+        Some(age) => timestamp.is_some_and(|t| now.saturating_sub(t) <= age),
+        None => true,
+    };
+    let description = args.description.as_deref().map(str::to_lowercase);
+    let last_opened: HashMap<String, u64> = history
+        .entries(now)
+        .into_iter()
+        .map(|entry| (entry.project, entry.last_opened))
+        .collect();
+
+    let sort = args.sort.unwrap_or(ListSort::Name);
+    let columns = &config.list.columns;
+    // Structured output always includes everything except the size, which is expensive.
+    let shows = |column: ListColumn| structured || (args.long && columns.contains(&column));
+
+    let mut list: Vec<ListEntry> = Vec::new();
+    for project in projects.get_vec().iter() {
+        if filter
+            .as_ref()
+            .is_some_and(|glob| !glob.is_match(project.get_name()))
+        {
+            continue;
+        }
+
+        let metadata = read_metadata(&store, Path::new(project.get_path()));
+        let created = metadata.created.or_else(|| project.created());
+        let opened = metadata
+            .last_opened
+            .or_else(|| last_opened.get(project.get_name()).copied());
+        let matches = args.tag.iter().all(|tag| metadata.has_tag(tag))
+            && (!args.pinned || metadata.pinned)
+            && description
+                .as_deref()
+                .is_none_or(|text| metadata.description.to_lowercase().contains(text))
+            && within(created, args.created_within)
+            && within(opened, args.opened_within)
+            && args
+                .lang
+                .iter()
+                .all(|language| project.project_type().has_language(*language));
+        if !matches {
+            continue;
+        }
+
+        let path = Path::new(project.get_path());
+        list.push(ListEntry {
+            project,
+            created,
+            opened,
+            modified: (sort == ListSort::Modified || shows(ListColumn::Modified))
+                .then(|| project.modified())
+                .flatten(),
+            size: (sort == ListSort::Size || (args.long && columns.contains(&ListColumn::Size)))
+                .then(|| project.size()),
+            git: shows(ListColumn::Git).then(|| git::status(path)).flatten(),
+            metadata,
+        });
+    }
+
+    if list.is_empty() && !structured {
+        println!("No projects match the filters.");
+        return Ok(());
+    }
+
+    let by_name = |a: &ListEntry, b: &ListEntry| a.project.get_name().cmp(b.project.get_name());
+    match sort {
+        ListSort::Name => list.sort_by(by_name),
+        ListSort::Frecency => {
+            let scores = history.scores(now);
+            let score = |e: &ListEntry| {
+                scores
+                    .get(e.project.get_name())
+                    .copied()
+                    .unwrap_or_default()
+            };
+            list.sort_by(|a, b| score(b).total_cmp(&score(a)).then_with(|| by_name(a, b)));
+        }
+        ListSort::Modified => {
+            list.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| by_name(a, b)))
+        }
+        ListSort::Created => {
+            list.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| by_name(a, b)))
+        }
+        ListSort::Opened => {
+            list.sort_by(|a, b| b.opened.cmp(&a.opened).then_with(|| by_name(a, b)))
+        }
+        ListSort::Size => list.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| by_name(a, b))),
+    }
+    if args.reverse {
+        list.reverse();
+    }
+    // Pinned projects always come first, the order within both groups is kept.
+    list.sort_by_key(|entry| !entry.metadata.pinned);
+
+    if structured {
+        let list: Vec<ProjectInfo> = list
+            .into_iter()
+            .map(|entry| {
+                let project_type = entry.project.project_type();
+                ProjectInfo {
+                    name: entry.project.get_name().to_string(),
+                    path: std::path::absolute(entry.project.get_path())
+                        .unwrap_or_else(|_| Path::new(entry.project.get_path()).to_path_buf()),
+                    root: entry.project.get_root().to_string(),
+                    tags: entry.metadata.tags,
+                    description: entry.metadata.description,
+                    pinned: entry.metadata.pinned,
+                    language: project_type.language().map(|l| l.name().to_string()),
+                    languages: project_type
+                        .languages
+                        .iter()
+                        .map(|l| l.name().to_string())
+                        .collect(),
+                    build_systems: project_type
+                        .build_systems
+                        .iter()
+                        .map(|b| b.name().to_string())
+                        .collect(),
+                    created: entry.created,
+                    last_opened: entry.opened,
+                    modified: entry.modified,
+                    size: entry.size,
+                    git: entry.git.as_ref().map(GitInfo::from),
+                }
+            })
+            .collect();
+        output::print(&list)?;
+        return Ok(());
+    }
+
+    if !args.pure {
+        print_title("Your projects");
+    }
+
+    if args.long {
+        print_table(&list, columns, args.pure, now);
+        return Ok(());
+    }
+
+    for entry in list {
+        let project_name = entry.project.get_name();
+        if args.pure {
+            println!("{project_name}");
+        } else {
+            let root = if show_roots {
+                format!("[{}]", entry.project.get_root()).dimmed()
+            } else {
+                "".dimmed()
+            };
+            let is_recent = if project_name == recent {
+                "(recent)".white().bold()
+            } else {
+                "".dimmed()
+            };
+            let pinned = if entry.metadata.pinned {
+                "(pinned)".yellow().bold()
+            } else {
+                "".dimmed()
+            };
+            let tags: Vec<String> = entry
+                .metadata
+                .tags
+                .iter()
+                .map(|tag| format!("#{tag}"))
+                .collect();
+            println!(
+                " {project_name} {root} {is_recent} {pinned} {} {}",
+                tags.join(" ").cyan(),
+                entry.metadata.description.dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
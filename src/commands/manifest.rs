@@ -0,0 +1,197 @@
+use std::{collections::HashSet, path::Path, time::Duration};
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+use indicatif::{MultiProgress, ProgressStyle};
+
+use crate::{
+    cli::{CloneArgs, ExportManifestArgs},
+    commands::{metadata::read_metadata, root::load_library},
+    config::{Config, DEFAULT_ROOT},
+    git, history,
+    library::{CloneOptions, Library, LibraryError},
+    manifest::{Manifest, ManifestEntry},
+    metadata::MetadataStore,
+    parallel, platform,
+    terminal::{generate_progress, print_done},
+};
+
+enum CloneOutcome {
+    Cloned,
+    Skipped,
+    /// Another entry of the manifest is cloned into the same directory.
+    Duplicate,
+    Failed(String),
+}
+
+fn clone_entry(library: &Library, options: &CloneOptions) -> CloneOutcome {
+    match library.clone(options) {
+        Ok(_) => CloneOutcome::Cloned,
+        Err(LibraryError::AlreadyExists) => CloneOutcome::Skipped,
+        Err(e) => CloneOutcome::Failed(e.to_string()),
+    }
+}
+
+/// Clones every repository of the manifest that does not exist in the library yet.
+pub fn handle_clone(config: &Config, path: &Path, args: &CloneArgs) -> Result<()> {
+    let manifest = Manifest::load(path)?;
+    if manifest.repositories.is_empty() {
+        println!("The manifest has no repositories.");
+        return Ok(());
+    }
+
+    let library = load_library(config, None)?;
+
+    // Entries that end up in the same directory would race each other, and the failed
+    // clone would remove the directory of the successful one. Only the first one is cloned.
+    let mut targets = HashSet::new();
+    let jobs: Vec<(CloneOptions, bool)> = manifest
+        .repositories
+        .iter()
+        .map(|entry| {
+            let mut options = entry.clone_options();
+            options.remote = config.clone.expand_remote(&options.remote);
+            options.layout = config.clone.layout;
+            options.depth = args.depth;
+            options.recurse_submodules = args.recurse_submodules;
+            options.single_branch = args.single_branch;
+            options.bare = args.bare;
+            options.sparse = args.sparse.clone();
+            options.root = options.root.or_else(|| args.root.clone());
+            let duplicate = library
+                .clone_path(&options)
+                .is_ok_and(|path| !targets.insert(path));
+            (options, duplicate)
+        })
+        .collect();
+
+    let workers = args.jobs.map_or_else(parallel::default_jobs, usize::from);
+    let multi = MultiProgress::new();
+    let outcomes = parallel::map(&jobs, workers, |(options, duplicate)| {
+        let name = options.directory_name();
+
+        let progress = multi.add(generate_progress());
+        progress.set_message(format!("Cloning {name}..."));
+        progress.enable_steady_tick(Duration::from_millis(100));
+
+        let outcome = if *duplicate {
+            CloneOutcome::Duplicate
+        } else {
+            clone_entry(&library, options)
+        };
+        let message = match &outcome {
+            CloneOutcome::Cloned => format!("{} {name}", "✓".green().bold()),
+            CloneOutcome::Skipped => {
+                format!(
+                    "{} {name} {}",
+                    "-".yellow().bold(),
+                    "already exists".dimmed()
+                )
+            }
+            CloneOutcome::Duplicate => {
+                format!(
+                    "{} {name} {}",
+                    "-".yellow().bold(),
+                    "has the same directory as another repository".dimmed()
+                )
+            }
+            CloneOutcome::Failed(error) => {
+                format!("{} {name} {}", "✗".red().bold(), error.dimmed())
+            }
+        };
+        progress.set_style(ProgressStyle::with_template(" {msg}").unwrap());
+        progress.finish_with_message(message);
+        outcome
+    });
+
+    // Metadata is written once after all clones, so that parallel jobs do not race on the file.
+    let metadata_path = platform::metadata_file();
+    let mut store = MetadataStore::load(&metadata_path)?;
+    let now = history::now();
+    for ((outcome, (options, _)), entry) in outcomes
+        .iter()
+        .zip(jobs.iter())
+        .zip(manifest.repositories.iter())
+    {
+        if !matches!(outcome, CloneOutcome::Cloned) {
+            continue;
+        }
+        let project_path = library.clone_path(options)?;
+        store.update(&project_path, |metadata| {
+            metadata.created = Some(now);
+            for tag in entry.tags.iter() {
+                metadata.add_tag(tag);
+            }
+        })?;
+    }
+    store.save(metadata_path)?;
+
+    let (mut cloned, mut failed, mut skipped) = (0, 0, 0);
+    for outcome in outcomes.iter() {
+        match outcome {
+            CloneOutcome::Cloned => cloned += 1,
+            CloneOutcome::Failed(_) => failed += 1,
+            CloneOutcome::Skipped | CloneOutcome::Duplicate => skipped += 1,
+        }
+    }
+    println!(
+        " {} cloned, {} failed, {} skipped",
+        cloned.to_string().green().bold(),
+        failed.to_string().red().bold(),
+        skipped.to_string().yellow().bold()
+    );
+
+    if failed > 0 {
+        bail!("{failed} repositories failed to clone.");
+    }
+    Ok(())
+}
+
+/// Writes the remotes of all Git projects into a manifest.
+pub fn handle_export(args: ExportManifestArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let library = load_library(&config, args.root.as_deref())?;
+    let store = MetadataStore::load(platform::metadata_file())?;
+
+    let mut projects: Vec<_> = library.get_vec().iter().collect();
+    projects.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+    let mut manifest = Manifest::default();
+    for project in projects {
+        let path = Path::new(project.get_path());
+        let Some(remote) = git::remote_url(path) else {
+            continue;
+        };
+
+        let directory = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let derived = CloneOptions {
+            remote: remote.clone(),
+            ..Default::default()
+        }
+        .directory_name();
+
+        manifest.repositories.push(ManifestEntry {
+            remote,
+            name: (directory != derived).then_some(directory),
+            branch: None,
+            root: (project.get_root() != DEFAULT_ROOT).then(|| project.get_root().to_string()),
+            tags: read_metadata(&store, path).tags,
+        });
+    }
+
+    match args.file {
+        Some(file) => {
+            manifest.save(&file)?;
+            print_done(&format!(
+                "Exported {} repositories to {}.",
+                manifest.repositories.len(),
+                file.display()
+            ));
+        }
+        None => print!("{}", manifest.to_toml()?),
+    }
+    Ok(())
+}
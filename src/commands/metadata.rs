@@ -0,0 +1,163 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Result, anyhow};
+use colored::Colorize;
+
+use crate::{
+    autocomplete::MatchPolicy,
+    cli::{DescribeArgs, PinArgs, TagArgs, TagListArgs},
+    commands::root::{load_history, load_library, pick_project, resolve_project_name},
+    config::Config,
+    library::Library,
+    metadata::{MetadataStore, ProjectMetadata},
+    output::{self, TagInfo},
+    platform,
+    terminal::{print_done, print_title, print_warning},
+};
+
+/// Metadata of the project. A broken `.kanri.toml` is reported and the central metadata
+/// is used instead, so that one project does not break commands that read all of them.
+pub(crate) fn read_metadata(store: &MetadataStore, project_path: &Path) -> ProjectMetadata {
+    store.get(project_path).unwrap_or_else(|e| {
+        print_warning(&format!("{e} Using the central metadata instead."));
+        store.get_central(project_path)
+    })
+}
+
+/// Finds the project by name, or lets the user pick one if no name is given.
+fn select_project(config: &Config, projects: &Library, name: Option<String>) -> Result<String> {
+    let history = load_history(config)?;
+    match name {
+        Some(name) => resolve_project_name(&name, config, projects, &history, MatchPolicy::Lenient)
+            .ok_or_else(|| anyhow!("Project not found.")),
+        None => pick_project(config, projects, &history)?
+            .ok_or_else(|| anyhow!("Provide a name of the project.")),
+    }
+}
+
+fn update_tags(args: TagArgs, add: bool) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let name = select_project(&config, &projects, Some(args.name))?;
+    let project = projects.get(&name)?;
+
+    let metadata_path = platform::metadata_file();
+    let mut store = MetadataStore::load(&metadata_path)?;
+    let mut changed = 0;
+    store.update(Path::new(project.get_path()), |metadata| {
+        for tag in args
+            .tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+        {
+            let updated = if add {
+                metadata.add_tag(tag)
+            } else {
+                metadata.remove_tag(tag)
+            };
+            changed += usize::from(updated);
+        }
+    })?;
+    store.save(metadata_path)?;
+
+    let action = if add { "Added" } else { "Removed" };
+    print_done(&format!("{action} {changed} tag(s)."));
+    Ok(())
+}
+
+pub fn handle_tag_add(args: TagArgs) -> Result<()> {
+    update_tags(args, true)
+}
+
+pub fn handle_tag_remove(args: TagArgs) -> Result<()> {
+    update_tags(args, false)
+}
+
+pub fn handle_tag_list(args: TagListArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, None)?;
+    let store = MetadataStore::load(platform::metadata_file())?;
+
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for project in projects.get_vec().iter() {
+        for tag in read_metadata(&store, Path::new(project.get_path())).tags {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+
+    if output::is_structured() {
+        let tags: Vec<TagInfo> = counts
+            .into_iter()
+            .map(|(name, projects)| TagInfo { name, projects })
+            .collect();
+        output::print(&tags)?;
+        return Ok(());
+    }
+
+    if counts.is_empty() {
+        println!("No tags found.");
+        return Ok(());
+    }
+
+    if !args.pure {
+        print_title("Tags");
+    }
+    for (tag, count) in counts.iter() {
+        if args.pure {
+            println!("{tag}");
+        } else {
+            let projects = if *count == 1 { "project" } else { "projects" };
+            println!(" {tag} {}", format!("({count} {projects})").dimmed());
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_describe(args: DescribeArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let name = select_project(&config, &projects, args.name)?;
+    let project = projects.get(&name)?;
+    let project_path = Path::new(project.get_path());
+
+    let metadata_path = platform::metadata_file();
+    let mut store = MetadataStore::load(&metadata_path)?;
+
+    let description = match (args.description, args.clear) {
+        (Some(description), _) => description.trim().to_string(),
+        (None, true) => String::new(),
+        (None, false) => {
+            let metadata = read_metadata(&store, project_path);
+            if metadata.description.is_empty() {
+                println!("No description.");
+            } else {
+                println!("{}", metadata.description);
+            }
+            return Ok(());
+        }
+    };
+
+    let cleared = description.is_empty();
+    store.update(project_path, |metadata| metadata.description = description)?;
+    store.save(metadata_path)?;
+    print_done(if cleared { "Cleared." } else { "Updated." });
+    Ok(())
+}
+
+pub fn handle_pin(args: PinArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let name = select_project(&config, &projects, args.name)?;
+    let project = projects.get(&name)?;
+
+    let metadata_path = platform::metadata_file();
+    let mut store = MetadataStore::load(&metadata_path)?;
+    store.update(Path::new(project.get_path()), |metadata| {
+        metadata.pinned = !args.unpin
+    })?;
+    store.save(metadata_path)?;
+
+    print_done(if args.unpin { "Unpinned." } else { "Pinned." });
+    Ok(())
+}
@@ -1,3 +1,9 @@
 pub mod config;
+pub mod list;
+pub mod manifest;
+pub mod metadata;
 pub mod root;
+pub mod status;
+pub mod sync;
 pub mod templates;
+pub mod trash;
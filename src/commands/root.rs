@@ -1,97 +1,316 @@
 use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, IsTerminal},
     path::Path,
     time::{Duration, Instant},
 };
 
-use anyhow::{Result, anyhow, ensure};
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::ValueEnum;
+use clap_complete::env::Shells;
 use colored::Colorize;
 
 use crate::{
-    autocomplete,
-    cli::{CloneArgs, ListArgs, NewArgs, OpenArgs, RemoveArgs, RenameArgs},
-    config::Config,
-    library::{CloneOptions, Library},
+    archive::Archives,
+    autocomplete::{self, MatchPolicy},
+    cli::{
+        ArchiveArgs, COMPLETE_VAR, CloneArgs, CompletionsArgs, InitShell, NewArgs, OpenArgs,
+        PathArgs, RecentArgs, RemoveArgs, RenameArgs, ShellInitArgs, UnarchiveArgs,
+    },
+    commands::{manifest, metadata::read_metadata},
+    config::{Config, ProjectTraits},
+    history::{self, History},
+    ignore::IgnoreRules,
+    integration,
+    library::{CloneLayout, CloneOptions, Library, LibraryError, Root, ScanOptions},
+    metadata::MetadataStore,
+    output::{self, PathInfo, RecentProjectInfo},
     platform,
     program::{LaunchOptions, launch_program},
-    templates::Templates,
+    templates::{self, TemplatePlan, TemplateVariable, Templates},
     terminal::{
-        ask_dialog, generate_progress, print_done, print_error, print_progress, print_title,
+        ask_dialog, ask_select_dialog, ask_validated_dialog, format_elapsed, format_size,
+        generate_progress, pick_dialog, print_done, print_error, print_progress, print_title,
+        print_warning,
     },
+    trash::Trash,
 };
 
-fn resolve_project_name(project_name: &str, config: &Config, projects: &Library) -> Option<String> {
-    if project_name == "-" && config.recent.enabled {
-        Some(config.recent.recent_project.clone())
+/// Parses `-`, `-2`, `-3`... into a position in the history of recently opened projects.
+fn parse_recent_shortcut(name: &str) -> Option<usize> {
+    let position = name.strip_prefix('-')?;
+    if position.is_empty() {
+        return Some(1);
+    }
+    position.parse::<usize>().ok().filter(|n| *n > 0)
+}
+
+pub(crate) fn resolve_project_name(
+    project_name: &str,
+    config: &Config,
+    projects: &Library,
+    history: &History,
+    policy: MatchPolicy,
+) -> Option<String> {
+    if config.recent.enabled
+        && let Some(position) = parse_recent_shortcut(project_name)
+    {
+        history.recent(position).map(str::to_string)
     } else if config.autocomplete.enabled {
-        autocomplete::autocomplete(project_name, projects.get_names(), config)
+        let scores = history.scores(history::now());
+        autocomplete::autocomplete(project_name, projects.get_names(), &scores, config, policy)
     } else {
         Some(project_name.to_string())
     }
 }
 
+/// Lets the user pick a project interactively.
+/// Returns `None` if stdin is not a terminal, so callers can report a missing name.
+pub(crate) fn pick_project(
+    config: &Config,
+    projects: &Library,
+    history: &History,
+) -> Result<Option<String>> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Ok(None);
+    }
+    ensure!(!projects.is_empty(), "No projects found.");
+
+    let recent = history.recent(1).unwrap_or_default();
+    let width = projects
+        .get_names()
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or_default();
+    let items: Vec<String> = projects
+        .get_vec()
+        .iter()
+        .map(|project| {
+            let name = project.get_name();
+            let marker = if config.recent.enabled && name == recent {
+                " (recent)"
+            } else {
+                ""
+            };
+            format!("{name:width$}  {}{marker}", project.get_path())
+        })
+        .collect();
+
+    let index = pick_dialog("Select a project", &items).ok_or_else(|| anyhow!("Canceled."))?;
+    Ok(Some(projects.get_vec()[index].get_name().to_string()))
+}
+
+/// Loads the library from all configured roots, or only from `root` if it is given.
+pub(crate) fn load_library(config: &Config, root: Option<&str>) -> Result<Library> {
+    let mut roots: Vec<Root> = config
+        .roots()
+        .into_iter()
+        .map(|(name, path)| Root::new(&name, path))
+        .collect();
+
+    if let Some(root_name) = root {
+        roots.retain(|r| r.name == root_name);
+        let Some(selected) = roots.first() else {
+            bail!("Root '{root_name}' is not defined in the configuration file.");
+        };
+        ensure!(
+            selected.path.is_dir(),
+            "Root '{root_name}' does not exist: {}.",
+            selected.path.display()
+        );
+    }
+
+    // A single stale root should not break every command, so missing roots are skipped.
+    // Shell completion runs silently, so it does not print the warning.
+    let (roots, missing): (Vec<Root>, Vec<Root>) =
+        roots.into_iter().partition(|root| root.path.is_dir());
+    if env::var_os(COMPLETE_VAR).is_none() {
+        for root in missing.iter() {
+            print_warning(&format!(
+                "Root '{}' does not exist and is skipped: {}.",
+                root.name,
+                root.path.display()
+            ));
+        }
+    }
+
+    let options = ScanOptions {
+        display_hidden: config.options.display_hidden,
+        max_depth: config.groups.max_depth,
+        markers: config.groups.markers.clone(),
+        ignore: IgnoreRules::new(&config.ignore)?,
+        host_layout: config.clone.layout == CloneLayout::HostOwnerRepo,
+    };
+    Ok(Library::new(roots, &options)?)
+}
+
+/// Loads the history of opened projects, migrating the recent project of older
+/// configuration files into it.
+pub(crate) fn load_history(config: &Config) -> Result<History> {
+    let history_path = platform::history_file();
+    let mut history = History::load(&history_path)?;
+    if history.migrate_recent(&config.recent.recent_project, history::now()) {
+        history.save(&history_path)?;
+    }
+    Ok(history)
+}
+
+/// Asks the user for a value of the template variable.
+/// Returns `None` if stdin is not a terminal, so the default value is used instead.
+fn ask_variable(variable: &TemplateVariable) -> Option<String> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return None;
+    }
+
+    let question = if variable.description.is_empty() {
+        variable.name.clone()
+    } else {
+        format!("{} ({})", variable.description, variable.name)
+    };
+    let default = variable.default.clone().unwrap_or_default();
+
+    if !variable.choices.is_empty() {
+        let selected = variable
+            .choices
+            .iter()
+            .position(|c| *c == default)
+            .unwrap_or_default();
+        let index = ask_select_dialog(&question, &variable.choices, selected);
+        return Some(variable.choices[index].clone());
+    }
+
+    Some(ask_validated_dialog(&question, &default, |value| {
+        if value.is_empty() && variable.default.is_some() {
+            return Ok(());
+        }
+        variable.validate(value).map_err(|e| e.to_string())
+    }))
+}
+
+/// Copies skeleton files and runs the steps of the plan.
+fn apply_plan(plan: &TemplatePlan, quiet: bool) -> Result<()> {
+    if let Some(skeleton) = &plan.skeleton {
+        templates::render_tree(skeleton, &plan.project_path, &plan.values)?;
+    }
+
+    let total_steps = plan.steps.len() as i8;
+    for (idx, step) in plan.steps.iter().enumerate() {
+        print_progress(&step.label, idx as i8 + 1, total_steps);
+        fs::create_dir_all(&step.cwd)?;
+
+        let launch_options = LaunchOptions {
+            program: step.program.clone(),
+            args: step.args.clone(),
+            cwd: Some(step.cwd.to_string_lossy().to_string()),
+            fork_mode: false,
+            quiet,
+            env: Some(step.env.clone()),
+            timeout: step.timeout,
+        };
+
+        match launch_program(launch_options) {
+            Ok(()) => {}
+            Err(e) if step.continue_on_error => {
+                print_error(&format!("Template command '{}' failed: {}", step.label, e));
+            }
+            Err(e) => bail!("Template command '{}' failed: {}", step.label, e),
+        }
+    }
+    Ok(())
+}
+
+fn print_plan(plan: &TemplatePlan) {
+    println!(" Create directory {}", plan.project_path.display());
+    if let Some(skeleton) = &plan.skeleton {
+        println!(" Copy files from {}", skeleton.display());
+    }
+
+    let total_steps = plan.steps.len() as i8;
+    for (idx, step) in plan.steps.iter().enumerate() {
+        print_progress(&step.label, idx as i8 + 1, total_steps);
+        let env: Vec<String> = step.env.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        println!("    {} {}", "program:".dimmed(), step.program);
+        println!("    {} {:?}", "args:".dimmed(), step.args);
+        println!("    {} {}", "cwd:".dimmed(), step.cwd.display());
+        println!("    {} {}", "env:".dimmed(), env.join(" "));
+        if let Some(timeout) = step.timeout {
+            println!("    {} {}s", "timeout:".dimmed(), timeout.as_secs());
+        }
+        if step.continue_on_error {
+            println!("    {} yes", "continue on error:".dimmed());
+        }
+    }
+}
+
+/// Remembers when the project was created with Kanri.
+fn record_created(project_path: &Path) -> Result<()> {
+    let metadata_path = platform::metadata_file();
+    let mut store = MetadataStore::load(&metadata_path)?;
+    store.update(project_path, |metadata| {
+        metadata.created = Some(history::now())
+    })?;
+    store.save(metadata_path)?;
+    Ok(())
+}
+
 pub fn handle_new(args: NewArgs) -> Result<()> {
     let config = Config::load(platform::config_file())?;
-    let projects = Library::new(
-        &config.options.projects_directory,
-        config.options.display_hidden,
-    )?;
+    let projects = load_library(&config, args.root.as_deref())?;
 
     let name = args
         .name
         .ok_or_else(|| anyhow!("Provide a name for a new project."))?;
+    let project_path = projects.base_path().join(&name);
+
+    // Resolve the template before creating the project, so nothing is left behind on errors.
+    let plan = match &args.template {
+        Some(template_name) => {
+            let templates = Templates::load(platform::templates_file())?;
+            let template = templates
+                .get_template(template_name)
+                .ok_or_else(|| anyhow!("Template '{}' not found.", template_name))?;
+            let provided: HashMap<String, String> = args.vars.into_iter().collect();
+            let values = template.resolve_variables(&provided, ask_variable)?;
+            Some(template.plan(template_name, &name, &project_path, &values, &config.shell)?)
+        }
+        None => None,
+    };
 
-    projects.create(&name)?;
+    if args.dry_run {
+        ensure!(!project_path.exists(), LibraryError::AlreadyExists);
+        println!("Dry run, nothing will be changed.");
+        match &plan {
+            Some(plan) => print_plan(plan),
+            None => println!(" Create directory {}", project_path.display()),
+        }
+        return Ok(());
+    }
 
-    if let Some(template_name) = args.template {
-        let templates = Templates::load(platform::templates_file())?;
-        let template = templates
-            .get_template(&template_name)
-            .ok_or_else(|| anyhow!("Template '{}' not found.", template_name))?;
-        let started_time = Instant::now();
+    let project_path = projects.create(&name)?;
 
-        println!("Generating project from '{}' template...", &name);
+    if let Some(plan) = plan {
+        let started_time = Instant::now();
 
-        let program = &config.shell.program;
-        ensure!(
-            !program.is_empty(),
-            "Shell is not configured in the configuration file."
+        println!(
+            "Generating project from '{}' template...",
+            args.template.unwrap_or_default()
         );
-        let project_path = Path::new(&config.options.projects_directory)
-            .join(&name)
-            .to_string_lossy()
-            .to_string();
-        let total_commands = template.len() as i8;
-
-        for (idx, command) in template.iter().enumerate() {
-            let current = idx as i8 + 1;
-            print_progress(command, current, total_commands);
-
-            let mut args_vec = config.shell.args.clone();
-            args_vec.push(command.clone());
-
-            let env_map = Vec::from([(String::from("ENJO_PROJECT"), name.clone())]);
-
-            let launch_options = LaunchOptions {
-                program: program.to_string(),
-                args: args_vec,
-                cwd: Some(project_path.clone()),
-                fork_mode: false,
-                quiet: args.quiet,
-                env: Some(env_map),
-            };
 
-            if let Err(e) = launch_program(launch_options) {
-                print_error("Failed to apply template. Cleaning up...");
-                projects
-                    .delete(&name)
-                    .map_err(|err| anyhow!("Additionally, cleanup failed: {}", err.to_string()))?;
-                return Err(anyhow!("Template command '{}' failed: {}", command, e));
-            }
+        if let Err(e) = apply_plan(&plan, args.quiet) {
+            print_error("Failed to apply template. Cleaning up...");
+            // Remove only the directory made above, never a project with the same name in another root.
+            fs::remove_dir_all(&project_path)
+                .map_err(|err| anyhow!("Additionally, cleanup failed: {}", err.to_string()))?;
+            return Err(e);
         }
+        record_created(&project_path)?;
 
         let elapsed_time = started_time.elapsed().as_millis();
         print_done(&format!("Generated in {elapsed_time} ms."));
     } else {
+        record_created(&project_path)?;
         print_done("Created.");
     }
 
@@ -100,57 +319,105 @@ pub fn handle_new(args: NewArgs) -> Result<()> {
 
 pub fn handle_clone(args: CloneArgs) -> Result<()> {
     let config = Config::load(platform::config_file())?;
+    if let Some(manifest) = &args.manifest {
+        return manifest::handle_clone(&config, manifest, &args);
+    }
 
     let remote = args
         .remote
         .ok_or_else(|| anyhow!("You need to provide a remote URL."))?;
 
     let clone_options = CloneOptions {
-        remote,
+        remote: config.clone.expand_remote(&remote),
         name: args.name,
         branch: args.branch,
+        quiet: args.quiet,
+        layout: config.clone.layout,
+        depth: args.depth,
+        recurse_submodules: args.recurse_submodules,
+        single_branch: args.single_branch,
+        bare: args.bare,
+        sparse: args.sparse,
+        ..Default::default()
     };
 
-    let projects = Library::new(
-        &config.options.projects_directory,
-        config.options.display_hidden,
-    )?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let project_path = projects.clone_path(&clone_options)?;
+    ensure!(!project_path.exists(), LibraryError::AlreadyExists);
+
+    // Resolve the template before cloning, so a missing template or variable fails early.
+    let plan = match &args.template {
+        Some(template_name) => {
+            let templates = Templates::load(platform::templates_file())?;
+            let template = templates
+                .get_template(template_name)
+                .ok_or_else(|| anyhow!("Template '{}' not found.", template_name))?;
+            let provided: HashMap<String, String> = args.vars.into_iter().collect();
+            let values = template.resolve_variables(&provided, ask_variable)?;
+            let name = clone_options.directory_name();
+            Some(template.plan(template_name, &name, &project_path, &values, &config.shell)?)
+        }
+        None => None,
+    };
 
     projects
         .clone(&clone_options)
         .map_err(|e| anyhow!(e.to_string()))?;
 
+    if let Some(plan) = plan {
+        println!(
+            "Applying '{}' template...",
+            args.template.unwrap_or_default()
+        );
+        if let Err(e) = apply_plan(&plan, args.quiet) {
+            print_error("Failed to apply template. Cleaning up...");
+            projects.remove_clone(&project_path);
+            return Err(e);
+        }
+    }
+    record_created(&project_path)?;
+
     print_done("Cloned.");
     Ok(())
 }
 
 pub fn handle_open(args: OpenArgs) -> Result<()> {
-    let config_path = platform::config_file();
-    let mut config = Config::load(&config_path)?;
-    let projects = Library::new(
-        &config.options.projects_directory,
-        config.options.display_hidden,
-    )?;
-
-    let project_name = args
-        .name
-        .ok_or_else(|| anyhow!("Project name is required."))?;
-
-    let name = resolve_project_name(&project_name, &config, &projects)
-        .ok_or_else(|| anyhow!("Project not found."))?;
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let history_path = platform::history_file();
+    let mut history = load_history(&config)?;
+
+    let name = match args.name {
+        Some(project_name) => resolve_project_name(
+            &project_name,
+            &config,
+            &projects,
+            &history,
+            MatchPolicy::Lenient,
+        )
+        .ok_or_else(|| anyhow!("Project not found."))?,
+        None => pick_project(&config, &projects, &history)?
+            .ok_or_else(|| anyhow!("Project name is required."))?,
+    };
 
     let project = projects
         .get(&name)
         .map_err(|_| anyhow!("Project not found."))?;
 
+    let metadata_path = platform::metadata_file();
+    let mut store = MetadataStore::load(&metadata_path)?;
+    let metadata = read_metadata(&store, Path::new(project.get_path()));
+    let traits = ProjectTraits {
+        name: project.get_name(),
+        project_type: project.project_type(),
+        tags: &metadata.tags,
+    };
     let (program, launch_args, fork_mode) = if args.shell {
-        (&config.shell.program, Vec::<String>::new(), false)
+        let shell = config.select_shell(args.with.as_deref(), &traits)?;
+        (&shell.program, Vec::<String>::new(), false)
     } else {
-        (
-            &config.editor.program,
-            config.editor.args.clone(),
-            config.editor.fork_mode,
-        )
+        let editor = config.select_editor(args.with.as_deref(), &traits)?;
+        (&editor.program, editor.args.clone(), editor.fork_mode)
     };
 
     ensure!(
@@ -158,9 +425,13 @@ pub fn handle_open(args: OpenArgs) -> Result<()> {
         "Required program is not specified in configuration file."
     );
 
-    if config.recent.enabled && name != config.recent.recent_project {
-        config.recent.recent_project = name.clone();
-        config.save(config_path)?;
+    if config.recent.enabled {
+        let now = history::now();
+        history.record(&name, now);
+        history.save(history_path)?;
+        if store.set_last_opened(Path::new(project.get_path()), now) {
+            store.save(metadata_path)?;
+        }
     }
 
     if args.shell {
@@ -177,6 +448,7 @@ pub fn handle_open(args: OpenArgs) -> Result<()> {
         fork_mode,
         quiet: false,
         env: None,
+        timeout: None,
     };
 
     if args.shell {
@@ -202,71 +474,60 @@ pub fn handle_open(args: OpenArgs) -> Result<()> {
     Ok(())
 }
 
-pub fn handle_list(args: ListArgs) -> Result<()> {
-    let config = Config::load(platform::config_file())?;
-    let projects = Library::new(
-        &config.options.projects_directory,
-        config.options.display_hidden,
-    )?;
-    if projects.is_empty() {
-        println!("No projects found.");
-        return Ok(());
-    }
-
-    let recent = &config.recent.recent_project;
-
-    if !args.pure {
-        print_title("Your projects");
-    }
-    for project in projects.get_vec().iter() {
-        let project_name = project.get_name();
-        if args.pure {
-            println!("{project_name}");
-        } else {
-            let is_recent = if project_name == recent.as_str() {
-                "(recent)".white().bold()
-            } else {
-                "".dimmed()
-            };
-            println!(" {project_name} {is_recent}");
-        }
-    }
-
-    Ok(())
-}
-
 pub fn handle_rename(args: RenameArgs) -> Result<()> {
     let config = Config::load(platform::config_file())?;
-    let projects = Library::new(
-        &config.options.projects_directory,
-        config.options.display_hidden,
-    )?;
-
-    let old_name = args
-        .old_name
-        .ok_or_else(|| anyhow!("Provide a project name to rename."))?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let history_path = platform::history_file();
+    let mut history = load_history(&config)?;
+
+    let old_name = match args.old_name {
+        Some(old_name) => old_name,
+        None => pick_project(&config, &projects, &history)?
+            .ok_or_else(|| anyhow!("Provide a project name to rename."))?,
+    };
     let new_name = args
         .new_name
         .ok_or_else(|| anyhow!("Provide a new name for a project."))?;
 
-    projects.rename(&old_name, &new_name)?;
+    let project = projects.get(&old_name)?;
+    let old_path = project.get_path().to_string();
+    let old_full_name = project.get_name().to_string();
+    let new_path = projects.rename(&old_name, &new_name)?;
+
+    let metadata_path = platform::metadata_file();
+    let mut store = MetadataStore::load(&metadata_path)?;
+    store.rename(Path::new(&old_path), &new_path);
+    store.save(metadata_path)?;
+
+    if !history.is_empty() {
+        // History has full names, e.g. `clients/acme/api2` after renaming `clients/acme/api`.
+        let renamed = load_library(&config, args.root.as_deref())?;
+        if let Some(project) = renamed
+            .get_vec()
+            .iter()
+            .find(|project| Path::new(project.get_path()) == new_path)
+        {
+            history.rename(&old_full_name, project.get_name());
+            history.save(history_path)?;
+        }
+    }
     print_done("Renamed.");
     Ok(())
 }
 
 pub fn handle_remove(args: RemoveArgs) -> Result<()> {
     let config = Config::load(platform::config_file())?;
-    let projects = Library::new(
-        &config.options.projects_directory,
-        config.options.display_hidden,
-    )?;
-
-    let name = args
-        .name
-        .ok_or_else(|| anyhow!("Provide a name of project to remove."))?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let history = load_history(&config)?;
 
-    let project_name = resolve_project_name(&name, &config, &projects)
-        .ok_or_else(|| anyhow!("Project not found."))?;
+    let project_name = match args.name {
+        Some(name) => {
+            resolve_project_name(&name, &config, &projects, &history, MatchPolicy::Strict)
+                .ok_or_else(|| anyhow!("Project not found."))?
+        }
+        None => pick_project(&config, &projects, &history)?
+            .ok_or_else(|| anyhow!("Provide a name of project to remove."))?,
+    };
 
     let project = projects
         .get(&project_name)
@@ -283,10 +544,177 @@ pub fn handle_remove(args: RemoveArgs) -> Result<()> {
     let spinner = generate_progress().with_message("Removing project...");
 
     spinner.enable_steady_tick(Duration::from_millis(100));
-    projects.delete(&project_name)?;
+    if args.permanent {
+        projects.delete(&project_name)?;
+        spinner.finish_and_clear();
+        print_done("Removed permanently.");
+    } else {
+        let trash = Trash::new(platform::trash_dir());
+        let entry = projects.move_to_trash(&project_name, &trash, history::now())?;
+        spinner.finish_and_clear();
+        print_done(&format!(
+            "Moved to the trash. Use `kanri trash restore {}` to bring it back.",
+            entry.name
+        ));
+    }
+    Ok(())
+}
+
+pub fn handle_archive(args: ArchiveArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let history = load_history(&config)?;
+
+    let project_name = match args.name {
+        Some(name) => {
+            resolve_project_name(&name, &config, &projects, &history, MatchPolicy::Strict)
+                .ok_or_else(|| anyhow!("Project not found."))?
+        }
+        None => pick_project(&config, &projects, &history)?
+            .ok_or_else(|| anyhow!("Provide a name of project to archive."))?,
+    };
+
+    let archives = Archives::new(config.archive.directory.clone());
+    let format = args.compression.unwrap_or(config.archive.format);
+    let exclude = if args.exclude_artifacts {
+        config.archive.artifacts.clone()
+    } else {
+        Vec::new()
+    };
+
+    let spinner = generate_progress().with_message("Archiving project...");
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    let result = projects.archive(&project_name, &archives, format, &exclude, history::now());
+    spinner.finish_and_clear();
+
+    let entry = result?;
+    print_done(&format!("Archived ({}).", format_size(entry.size)));
+    Ok(())
+}
+
+pub fn handle_unarchive(args: UnarchiveArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let name = args
+        .name
+        .ok_or_else(|| anyhow!("Provide a name of the archived project."))?;
+
+    let archives = Archives::new(config.archive.directory.clone());
+    let spinner = generate_progress().with_message("Unpacking project...");
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    let result = projects.unarchive(&name, &archives);
     spinner.finish_and_clear();
 
-    print_done("Removed.");
+    let path = result?;
+    print_done(&format!("Restored to {}.", path.display()));
+    Ok(())
+}
+
+pub fn handle_recent(args: RecentArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let history = load_history(&config)?;
+    let now = history::now();
+    let mut entries = history.entries(now);
+    if let Some(limit) = args.limit {
+        entries.truncate(limit);
+    }
+
+    if output::is_structured() {
+        let entries: Vec<RecentProjectInfo> = entries
+            .into_iter()
+            .map(|entry| RecentProjectInfo {
+                name: entry.project,
+                visits: entry.visits,
+                last_opened: entry.last_opened,
+                score: entry.score,
+            })
+            .collect();
+        output::print(&entries)?;
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No recent projects.");
+        return Ok(());
+    }
+
+    if !args.pure {
+        print_title("Recent projects");
+    }
+    for entry in entries.iter() {
+        if args.pure {
+            println!("{}", entry.project);
+        } else {
+            let visits = if entry.visits == 1 { "visit" } else { "visits" };
+            let details = format!(
+                "({} {visits}, {})",
+                entry.visits,
+                format_elapsed(entry.last_opened, now)
+            );
+            println!(" {} {}", entry.project, details.dimmed());
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_path(args: PathArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let history_path = platform::history_file();
+    let mut history = load_history(&config)?;
+
+    let name = match args.name {
+        Some(project_name) => resolve_project_name(
+            &project_name,
+            &config,
+            &projects,
+            &history,
+            MatchPolicy::Lenient,
+        )
+        .ok_or_else(|| anyhow!("Project not found."))?,
+        None => pick_project(&config, &projects, &history)?
+            .ok_or_else(|| anyhow!("Project name is required."))?,
+    };
+
+    let project = projects
+        .get(&name)
+        .map_err(|_| anyhow!("Project not found."))?;
+
+    if args.record && config.recent.enabled {
+        history.record(&name, history::now());
+        history.save(history_path)?;
+    }
+
+    if output::is_structured() {
+        output::print(&PathInfo::new(Path::new(project.get_path())))?;
+    } else {
+        println!("{}", project.get_path());
+    }
+    Ok(())
+}
+
+pub fn handle_cd() -> Result<()> {
+    bail!(
+        "Shell integration is not enabled. Add `eval \"$(kanri shell-init bash)\"` (or your shell) to your shell configuration."
+    )
+}
+
+pub fn handle_shell_init(args: ShellInitArgs) -> Result<()> {
+    let script = match args.shell {
+        InitShell::Bash | InitShell::Zsh => integration::POSIX_INIT,
+        InitShell::Fish => integration::FISH_INIT,
+        InitShell::Pwsh => integration::PWSH_INIT,
+    };
+    print!("{script}");
+    Ok(())
+}
+
+pub fn handle_completions(args: CompletionsArgs) -> Result<()> {
+    let shells = Shells::builtins();
+    let shell = shells
+        .completer(args.shell.to_possible_value().unwrap().get_name())
+        .ok_or_else(|| anyhow!("This shell is not supported."))?;
+    shell.write_registration(COMPLETE_VAR, "kanri", "kanri", "kanri", &mut io::stdout())?;
     Ok(())
 }
 
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use anyhow::Result;
+use colored::{ColoredString, Colorize};
+
+use crate::{
+    cli::StatusArgs,
+    commands::root::load_library,
+    config::Config,
+    git::{self, GitStatus},
+    library::Project,
+    output::{self, GitInfo, ProjectStatusInfo},
+    parallel, platform,
+    terminal::print_title,
+};
+
+struct StatusEntry<'a> {
+    project: &'a Project,
+    status: GitStatus,
+}
+
+const HEADERS: [&str; 6] = ["NAME", "BRANCH", "SYNC", "CHANGED", "UNTRACKED", "STASHES"];
+
+fn format_count(count: usize) -> String {
+    if count == 0 {
+        String::from("-")
+    } else {
+        count.to_string()
+    }
+}
+
+fn format_sync(status: &GitStatus) -> String {
+    match (status.ahead, status.behind) {
+        (0, 0) => String::from("-"),
+        (ahead, 0) => format!("↑{ahead}"),
+        (0, behind) => format!("↓{behind}"),
+        (ahead, behind) => format!("↑{ahead} ↓{behind}"),
+    }
+}
+
+fn cells(entry: &StatusEntry) -> [String; 6] {
+    let status = &entry.status;
+    [
+        entry.project.get_name().to_string(),
+        status
+            .branch
+            .clone()
+            .unwrap_or_else(|| String::from("(detached)")),
+        format_sync(status),
+        format_count(status.changed),
+        format_count(status.untracked),
+        format_count(status.stashes),
+    ]
+}
+
+fn paint(column: usize, status: &GitStatus, text: String) -> ColoredString {
+    let count = match column {
+        3 => status.changed,
+        4 => status.untracked,
+        5 => status.stashes,
+        _ => 0,
+    };
+    match column {
+        0 => text.bold(),
+        1 if status.is_dirty() => text.yellow(),
+        1 => text.green(),
+        2 if status.behind > 0 => text.red(),
+        2 if status.ahead > 0 => text.cyan(),
+        3 | 4 if count > 0 => text.yellow(),
+        5 if count > 0 => text.cyan(),
+        _ => text.dimmed(),
+    }
+}
+
+fn print_table(entries: &[StatusEntry], pure: bool) {
+    let rows: Vec<[String; 6]> = entries.iter().map(cells).collect();
+    if pure {
+        for row in rows {
+            println!("{}", row.join("\t"));
+        }
+        return;
+    }
+
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|idx| {
+            rows.iter()
+                .map(|row| row[idx].chars().count())
+                .chain([HEADERS[idx].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let header: Vec<String> = HEADERS
+        .iter()
+        .zip(widths.iter())
+        .map(|(header, width)| format!("{header:width$}"))
+        .collect();
+    println!(" {}", header.join("  ").trim_end().bold());
+
+    for (entry, row) in entries.iter().zip(rows) {
+        let cells: Vec<String> = row
+            .into_iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(idx, (text, width))| {
+                let text = if idx + 1 == HEADERS.len() {
+                    text
+                } else {
+                    format!("{text:width$}")
+                };
+                paint(idx, &entry.status, text).to_string()
+            })
+            .collect();
+        println!(" {}", cells.join("  "));
+    }
+}
+
+pub fn handle_status(args: StatusArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+
+    let repositories: Vec<&Project> = projects
+        .get_vec()
+        .iter()
+        .filter(|project| git::is_repository(Path::new(project.get_path())))
+        .collect();
+    let jobs = args.jobs.map_or_else(parallel::default_jobs, usize::from);
+    let statuses = parallel::map(&repositories, jobs, |project| {
+        git::status(Path::new(project.get_path()))
+    });
+
+    let mut entries: Vec<StatusEntry> = repositories
+        .into_iter()
+        .zip(statuses)
+        .filter_map(|(project, status)| {
+            Some(StatusEntry {
+                project,
+                status: status?,
+            })
+        })
+        .filter(|entry| !args.dirty_only || entry.status.is_dirty())
+        .collect();
+    entries.sort_by(|a, b| a.project.get_name().cmp(b.project.get_name()));
+
+    if output::is_structured() {
+        let entries: Vec<ProjectStatusInfo> = entries
+            .iter()
+            .map(|entry| ProjectStatusInfo {
+                name: entry.project.get_name().to_string(),
+                path: std::path::absolute(entry.project.get_path())
+                    .unwrap_or_else(|_| Path::new(entry.project.get_path()).to_path_buf()),
+                root: entry.project.get_root().to_string(),
+                git: GitInfo::from(&entry.status),
+            })
+            .collect();
+        output::print(&entries)?;
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        if args.dirty_only {
+            println!("All repositories are clean.");
+        } else {
+            println!("No Git repositories found.");
+        }
+        return Ok(());
+    }
+
+    if !args.pure {
+        print_title("Git status");
+    }
+    print_table(&entries, args.pure);
+
+    if !args.pure {
+        let dirty = entries.iter().filter(|e| e.status.is_dirty()).count();
+        let summary = format!("{} repositories, {dirty} with changes", entries.len());
+        println!(" {}", summary.dimmed());
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+use indicatif::{MultiProgress, ProgressDrawTarget};
+
+use crate::{
+    cli::SyncArgs,
+    commands::root::load_library,
+    config::Config,
+    git,
+    library::Project,
+    output::{self, SyncResultInfo},
+    parallel, platform,
+    program::{LaunchOptions, launch_captured},
+    terminal::generate_progress,
+};
+
+enum SyncOutcome {
+    Updated,
+    UpToDate,
+    Skipped(&'static str),
+    Failed(String),
+}
+
+impl SyncOutcome {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Updated => "updated",
+            Self::UpToDate => "up-to-date",
+            Self::Skipped(_) => "skipped",
+            Self::Failed(_) => "failed",
+        }
+    }
+
+    fn message(&self) -> Option<String> {
+        match self {
+            Self::Updated | Self::UpToDate => None,
+            Self::Skipped(reason) => Some(reason.to_string()),
+            Self::Failed(error) => Some(error.clone()),
+        }
+    }
+}
+
+fn git_args(pull: bool) -> Vec<String> {
+    let args: &[&str] = if pull {
+        &["pull", "--ff-only"]
+    } else {
+        &["fetch"]
+    };
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn sync_project(project: &Project, args: &SyncArgs) -> SyncOutcome {
+    let path = Path::new(project.get_path());
+    if !args.force {
+        match git::status(path) {
+            Some(status) if status.is_dirty() => return SyncOutcome::Skipped("has changes"),
+            Some(_) => {}
+            None => return SyncOutcome::Failed(String::from("Failed to read Git status")),
+        }
+    }
+
+    let before = git::refs(path);
+    let options = LaunchOptions {
+        program: String::from("git"),
+        args: git_args(args.pull),
+        cwd: Some(project.get_path().to_string()),
+        fork_mode: false,
+        quiet: true,
+        // Git must not wait for credentials on the terminal it does not have.
+        env: Some(vec![(
+            String::from("GIT_TERMINAL_PROMPT"),
+            String::from("0"),
+        )]),
+        timeout: None,
+    };
+    match launch_captured(options) {
+        Ok(()) if before.is_some() && git::refs(path) == before => SyncOutcome::UpToDate,
+        Ok(()) => SyncOutcome::Updated,
+        Err(e) => SyncOutcome::Failed(e.to_string()),
+    }
+}
+
+pub fn handle_sync(args: SyncArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = load_library(&config, args.root.as_deref())?;
+    let structured = output::is_structured();
+
+    let mut repositories: Vec<&Project> = projects
+        .get_vec()
+        .iter()
+        .filter(|project| git::is_repository(Path::new(project.get_path())))
+        .collect();
+    repositories.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+    if repositories.is_empty() && !structured {
+        println!("No Git repositories found.");
+        return Ok(());
+    }
+
+    let multi = MultiProgress::new();
+    if structured {
+        multi.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    let action = if args.pull { "Pulling" } else { "Fetching" };
+    let jobs = args.jobs.map_or_else(parallel::default_jobs, usize::from);
+    let outcomes = parallel::map(&repositories, jobs, |project| {
+        let progress = multi.add(generate_progress());
+        progress.set_message(format!("{action} {}...", project.get_name()));
+        progress.enable_steady_tick(Duration::from_millis(100));
+
+        let outcome = sync_project(project, &args);
+        let name = project.get_name();
+        let message = match &outcome {
+            SyncOutcome::Updated => format!("{} {name}", "✓".green().bold()),
+            SyncOutcome::UpToDate => {
+                format!("{} {name} {}", "✓".green().bold(), "up to date".dimmed())
+            }
+            SyncOutcome::Skipped(reason) => {
+                format!("{} {name} {}", "-".yellow().bold(), reason.dimmed())
+            }
+            SyncOutcome::Failed(error) => {
+                format!("{} {name} {}", "✗".red().bold(), error.dimmed())
+            }
+        };
+        progress.set_style(indicatif::ProgressStyle::with_template(" {msg}").unwrap());
+        progress.finish_with_message(message);
+        outcome
+    });
+
+    if structured {
+        let results: Vec<SyncResultInfo> = repositories
+            .iter()
+            .zip(outcomes.iter())
+            .map(|(project, outcome)| SyncResultInfo {
+                name: project.get_name().to_string(),
+                path: std::path::absolute(project.get_path())
+                    .unwrap_or_else(|_| Path::new(project.get_path()).to_path_buf()),
+                root: project.get_root().to_string(),
+                result: outcome.name().to_string(),
+                message: outcome.message(),
+            })
+            .collect();
+        output::print(&results)?;
+    } else {
+        let count = |name: &str| outcomes.iter().filter(|o| o.name() == name).count();
+        println!(
+            " {} updated, {} up to date, {} failed, {} skipped",
+            count("updated").to_string().green().bold(),
+            count("up-to-date").to_string().bold(),
+            count("failed").to_string().red().bold(),
+            count("skipped").to_string().yellow().bold()
+        );
+    }
+
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, SyncOutcome::Failed(_)))
+        .count();
+    if failed > 0 {
+        bail!("{failed} project(s) failed to sync.");
+    }
+    Ok(())
+}
@@ -1,11 +1,15 @@
 use anyhow::{Result, anyhow, bail, ensure};
+use colored::Colorize;
 
 use crate::{
     cli::{TemplatesInfoArgs, TemplatesListArgs, TemplatesRemoveArgs},
+    commands::config::edit_file,
     config::Config,
+    output::{
+        self, PathInfo, TemplateInfo, TemplateStepInfo, TemplateSummary, TemplateVariableInfo,
+    },
     platform,
-    program::{LaunchOptions, launch_program},
-    templates::Templates,
+    templates::{TemplateKind, Templates},
     terminal::{ask_dialog, ask_string_dialog, print_done, print_title},
 };
 
@@ -38,8 +42,29 @@ pub fn handle_new() -> Result<()> {
     Ok(())
 }
 
+fn kind_name(kind: TemplateKind) -> String {
+    match kind {
+        TemplateKind::Commands => String::from("commands"),
+        TemplateKind::Skeleton => String::from("skeleton"),
+    }
+}
+
 pub fn handle_list(args: TemplatesListArgs) -> Result<()> {
     let templates = Templates::load(platform::templates_file())?;
+    if output::is_structured() {
+        let mut names = templates.list_templates();
+        names.sort();
+        let list: Vec<TemplateSummary> = names
+            .into_iter()
+            .filter_map(|name| {
+                let kind = kind_name(templates.get_template(&name)?.kind);
+                Some(TemplateSummary { name, kind })
+            })
+            .collect();
+        output::print(&list)?;
+        return Ok(());
+    }
+
     if templates.is_empty() {
         println!("No templates found.");
         return Ok(());
@@ -56,30 +81,17 @@ pub fn handle_list(args: TemplatesListArgs) -> Result<()> {
 }
 
 pub fn handle_edit() -> Result<()> {
-    let templates_path = platform::templates_file();
-    let config = Config::load(&templates_path)?;
-    let editor = &config.editor.program;
-    if editor.is_empty() {
-        bail!("Editor program name is not set in the configuration file.");
-    }
-
-    let mut editor_args = config.editor.args.clone();
-    editor_args.push(templates_path.to_str().unwrap().to_string());
-
-    let launch_options = LaunchOptions {
-        program: editor.to_string(),
-        args: editor_args,
-        fork_mode: false,
-        quiet: false,
-        cwd: None,
-        env: None,
-    };
-
-    launch_program(launch_options).map_err(|e| anyhow!(e.to_string()))
+    let config = Config::load(platform::config_file())?;
+    edit_file(&config, &platform::templates_file())
 }
 
 pub fn handle_path() -> Result<()> {
-    println!("{}", platform::templates_file().display());
+    let path = platform::templates_file();
+    if output::is_structured() {
+        output::print(&PathInfo::new(&path))?;
+    } else {
+        println!("{}", path.display());
+    }
     Ok(())
 }
 
@@ -89,13 +101,100 @@ pub fn handle_info(args: TemplatesInfoArgs) -> Result<()> {
         .ok_or_else(|| anyhow!("Provide a name of the template."))?;
 
     let templates = Templates::load(platform::templates_file())?;
+    if output::is_structured() {
+        let template = templates
+            .get_template(&name)
+            .ok_or_else(|| anyhow!("Template not found."))?;
+        let info = TemplateInfo {
+            name: name.clone(),
+            kind: kind_name(template.kind),
+            skeleton: (template.kind == TemplateKind::Skeleton)
+                .then(|| platform::skeletons_dir().join(&name)),
+            variables: template
+                .variables
+                .iter()
+                .map(|variable| TemplateVariableInfo {
+                    name: variable.name.clone(),
+                    description: variable.description.clone(),
+                    default: variable.default.clone(),
+                    pattern: variable.pattern.clone(),
+                    choices: variable.choices.clone(),
+                })
+                .collect(),
+            steps: template
+                .commands
+                .iter()
+                .map(|step| TemplateStepInfo {
+                    command: step.command.clone(),
+                    label: step.label.clone(),
+                    cwd: step.cwd.clone(),
+                    env: step.env.clone(),
+                    timeout: step.timeout,
+                    continue_on_error: step.continue_on_error,
+                    shell: step.shell.clone(),
+                })
+                .collect(),
+        };
+        output::print(&info)?;
+        return Ok(());
+    }
+
     match templates.get_template(&name) {
         Some(template) => {
+            if template.kind == TemplateKind::Skeleton && !args.pure {
+                print_title("Skeleton directory");
+                println!(" {}", platform::skeletons_dir().join(&name).display());
+            }
+            if !template.variables.is_empty() && !args.pure {
+                print_title("Variables of this template");
+                for variable in template.variables.iter() {
+                    let mut details = Vec::new();
+                    if !variable.description.is_empty() {
+                        details.push(variable.description.clone());
+                    }
+                    if let Some(default) = &variable.default {
+                        details.push(format!("default: {default}"));
+                    }
+                    if !variable.choices.is_empty() {
+                        details.push(format!("one of: {}", variable.choices.join(", ")));
+                    }
+                    if let Some(pattern) = &variable.pattern {
+                        details.push(format!("matches: {pattern}"));
+                    }
+                    println!(" {} {}", variable.name, details.join("; ").dimmed());
+                }
+            }
             if !args.pure {
                 print_title("Commands of this template");
             }
-            for command in template.iter() {
-                println!("{}{command}", if args.pure { "" } else { " " });
+            for step in template.commands.iter() {
+                if args.pure {
+                    println!("{}", step.command);
+                    continue;
+                }
+
+                let mut details = Vec::new();
+                if let Some(label) = &step.label {
+                    details.push(format!("label: {label}"));
+                }
+                if let Some(cwd) = &step.cwd {
+                    details.push(format!("in: {cwd}"));
+                }
+                if !step.env.is_empty() {
+                    let env: Vec<String> =
+                        step.env.iter().map(|(k, v)| format!("{k}={v}")).collect();
+                    details.push(format!("env: {}", env.join(" ")));
+                }
+                if let Some(shell) = &step.shell {
+                    details.push(format!("shell: {shell}"));
+                }
+                if let Some(timeout) = step.timeout {
+                    details.push(format!("timeout: {timeout}s"));
+                }
+                if step.continue_on_error {
+                    details.push(String::from("continues on error"));
+                }
+                println!(" {} {}", step.command, details.join("; ").dimmed());
             }
         }
         None => {
@@ -0,0 +1,97 @@
+use anyhow::{Result, anyhow};
+use colored::Colorize;
+
+use crate::{
+    cli::{TrashListArgs, TrashPurgeArgs, TrashRestoreArgs},
+    history,
+    output::{self, TrashedProjectInfo},
+    platform,
+    terminal::{ask_dialog, format_elapsed, print_done, print_title},
+    trash::Trash,
+};
+
+pub fn handle_list(args: TrashListArgs) -> Result<()> {
+    let trash = Trash::new(platform::trash_dir());
+    let entries = trash.entries()?;
+    if output::is_structured() {
+        let entries: Vec<TrashedProjectInfo> = entries
+            .into_iter()
+            .map(|entry| TrashedProjectInfo {
+                name: entry.name,
+                path: entry.path,
+                root: entry.root,
+                deleted_at: entry.deleted_at,
+            })
+            .collect();
+        output::print(&entries)?;
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("Trash is empty.");
+        return Ok(());
+    }
+
+    if !args.pure {
+        print_title("Removed projects");
+    }
+
+    let now = history::now();
+    for entry in entries.iter() {
+        if args.pure {
+            println!("{}", entry.name);
+        } else {
+            let details = format!(
+                "[{}] (removed {}, was in {})",
+                entry.root,
+                format_elapsed(entry.deleted_at, now),
+                entry.path.display()
+            );
+            println!(" {} {}", entry.name, details.dimmed());
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_restore(args: TrashRestoreArgs) -> Result<()> {
+    let name = args
+        .name
+        .ok_or_else(|| anyhow!("Provide a name of the project to restore."))?;
+
+    let trash = Trash::new(platform::trash_dir());
+    let entry = trash.find(&name)?;
+    trash.restore(&entry)?;
+
+    print_done(&format!("Restored to {}.", entry.path.display()));
+    Ok(())
+}
+
+pub fn handle_purge(args: TrashPurgeArgs) -> Result<()> {
+    let trash = Trash::new(platform::trash_dir());
+    let now = history::now();
+    let entries: Vec<_> = trash
+        .entries()?
+        .into_iter()
+        .filter(|entry| {
+            args.older_than
+                .is_none_or(|age| now.saturating_sub(entry.deleted_at) > age)
+        })
+        .collect();
+
+    if entries.is_empty() {
+        println!("Nothing to purge.");
+        return Ok(());
+    }
+
+    let question = format!("Permanently delete {} project(s)?", entries.len());
+    if !args.force && !ask_dialog(&question, false) {
+        print_done("Canceled.");
+        return Ok(());
+    }
+
+    for entry in entries.iter() {
+        trash.purge(entry)?;
+    }
+    print_done("Purged.");
+    Ok(())
+}
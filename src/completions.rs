@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use clap_complete::CompletionCandidate;
+
+use crate::{
+    archive::Archives, commands::root::load_library, config::Config, metadata::MetadataStore,
+    platform, templates::Templates, trash::Trash,
+};
+
+// These functions are called by the shell while completing arguments, so they
+// must never fail loudly. Any error results in no candidates.
+
+pub fn project_names() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+    let Ok(projects) = load_library(&config, None) else {
+        return Vec::new();
+    };
+
+    projects
+        .get_vec()
+        .iter()
+        .map(|project| {
+            CompletionCandidate::new(project.get_name())
+                .help(Some(project.get_path().to_string().into()))
+        })
+        .collect()
+}
+
+pub fn tag_names() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+    let Ok(projects) = load_library(&config, None) else {
+        return Vec::new();
+    };
+    let Ok(store) = MetadataStore::load(platform::metadata_file()) else {
+        return Vec::new();
+    };
+
+    let mut tags: Vec<String> = projects
+        .get_vec()
+        .iter()
+        .filter_map(|project| store.get(Path::new(project.get_path())).ok())
+        .flat_map(|metadata| metadata.tags)
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags.into_iter().map(CompletionCandidate::new).collect()
+}
+
+pub fn profile_names() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+
+    let editors = config
+        .editors
+        .iter()
+        .map(|(name, editor)| (name, "editor", &editor.program));
+    let shells = config
+        .shells
+        .iter()
+        .map(|(name, shell)| (name, "shell", &shell.program));
+    editors
+        .chain(shells)
+        .map(|(name, kind, program)| {
+            CompletionCandidate::new(name).help(Some(format!("{kind}: {program}").into()))
+        })
+        .collect()
+}
+
+pub fn template_names() -> Vec<CompletionCandidate> {
+    let Ok(templates) = Templates::load(platform::templates_file()) else {
+        return Vec::new();
+    };
+
+    let mut names = templates.list_templates();
+    names.sort();
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+pub fn root_names() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+
+    config
+        .roots()
+        .into_iter()
+        .map(|(name, path)| {
+            CompletionCandidate::new(name).help(Some(path.display().to_string().into()))
+        })
+        .collect()
+}
+
+pub fn trashed_names() -> Vec<CompletionCandidate> {
+    let Ok(entries) = Trash::new(platform::trash_dir()).entries() else {
+        return Vec::new();
+    };
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            CompletionCandidate::new(entry.name).help(Some(entry.path.display().to_string().into()))
+        })
+        .collect()
+}
+
+pub fn archived_names() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+    let Ok(entries) = Archives::new(config.archive.directory).entries() else {
+        return Vec::new();
+    };
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            CompletionCandidate::new(entry.name).help(Some(entry.path.display().to_string().into()))
+        })
+        .collect()
+}
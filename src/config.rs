@@ -1,6 +1,14 @@
-use crate::platform;
+use crate::{
+    archive::ArchiveFormat,
+    detect::{BuildSystem, Language, ProjectType},
+    ignore::IgnoreRules,
+    library::CloneLayout,
+    platform,
+};
+use globset::Glob;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -22,16 +30,121 @@ pub enum ConfigError {
 
     #[error("File system error occured: {0}.")]
     FileSystemError(#[from] std::io::Error),
+
+    #[error("Editor profile '{0}' is not defined in the configuration file.")]
+    UnknownEditor(String),
+
+    #[error("Shell profile '{0}' is not defined in the configuration file.")]
+    UnknownShell(String),
+
+    #[error("Editor is not set in the configuration file.")]
+    EditorNotConfigured,
 }
 
-#[derive(Deserialize, Serialize, Default, Clone)]
+/// Name of the root created from `projects_directory`.
+pub const DEFAULT_ROOT: &str = "default";
+
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub options: GeneralOptions,
-    pub editor: EditorOptions,
+
+    /// Name of the profile from `editors` used when no rule picks another one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_editor: Option<String>,
+
+    /// Patterns in the syntax of `.gitignore` that hide directories in every root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+
+    /// Deprecated, use `editors` and `default_editor` instead.
+    /// Used when `default_editor` is not set, so that older configuration files keep working.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<EditorOptions>,
+
     pub shell: ShellOptions,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub editors: BTreeMap<String, EditorOptions>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub shells: BTreeMap<String, ShellOptions>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<OpenRule>,
     pub recent: RecentOptions,
     pub autocomplete: AutocompleteOptions,
+    pub archive: ArchiveOptions,
+    pub list: ListOptions,
+    pub clone: CloningOptions,
+    pub groups: GroupOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let editor = EditorOptions::default();
+        let name = Path::new(&editor.program)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or_else(|| String::from("default"));
+
+        Self {
+            options: GeneralOptions::default(),
+            default_editor: Some(name.clone()),
+            ignore: Vec::new(),
+            editor: None,
+            shell: ShellOptions::default(),
+            editors: BTreeMap::from([(name, editor)]),
+            shells: BTreeMap::new(),
+            rules: Vec::new(),
+            recent: RecentOptions::default(),
+            autocomplete: AutocompleteOptions::default(),
+            archive: ArchiveOptions::default(),
+            list: ListOptions::default(),
+            clone: CloningOptions::default(),
+            groups: GroupOptions::default(),
+        }
+    }
+}
+
+/// Picks editor and shell profiles for projects that match all of the given conditions.
+#[derive(Deserialize, Serialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct OpenRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildSystem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Glob matched against the name of the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+}
+
+impl OpenRule {
+    pub fn matches(&self, name: &str, project_type: &ProjectType, tags: &[String]) -> bool {
+        self.language
+            .is_none_or(|language| project_type.has_language(language))
+            && self
+                .build
+                .is_none_or(|build| project_type.build_systems.contains(&build))
+            && self.tag.as_ref().is_none_or(|tag| tags.contains(tag))
+            && self.name.as_deref().is_none_or(|pattern| {
+                Glob::new(pattern).is_ok_and(|glob| glob.compile_matcher().is_match(name))
+            })
+    }
+}
+
+/// What a project is matched by when picking editor and shell profiles.
+pub struct ProjectTraits<'a> {
+    pub name: &'a str,
+    pub project_type: &'a ProjectType,
+    pub tags: &'a [String],
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -39,6 +152,7 @@ pub struct Config {
 pub struct GeneralOptions {
     pub projects_directory: PathBuf,
     pub display_hidden: bool,
+    pub roots: BTreeMap<String, PathBuf>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -61,6 +175,10 @@ impl Default for AutocompleteOptions {
 #[serde(default, deny_unknown_fields)]
 pub struct RecentOptions {
     pub enabled: bool,
+
+    /// Deprecated, the history of opened projects is stored in `history.json` now.
+    /// Kept so that older configuration files still load.
+    #[serde(skip_serializing)]
     pub recent_project: String,
 }
 
@@ -78,6 +196,138 @@ impl Default for GeneralOptions {
         Self {
             projects_directory: platform::default_projects_dir(),
             display_hidden: false,
+            roots: BTreeMap::new(),
+        }
+    }
+}
+
+/// A column of the table printed by `list --long`.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ListColumn {
+    Name,
+    Root,
+    Path,
+    Modified,
+    Created,
+    Opened,
+    Size,
+    Language,
+    Build,
+    Git,
+    Tags,
+    Description,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListOptions {
+    /// Columns of `list --long`, in order.
+    pub columns: Vec<ListColumn>,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                ListColumn::Name,
+                ListColumn::Path,
+                ListColumn::Modified,
+                ListColumn::Language,
+                ListColumn::Git,
+            ],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct CloningOptions {
+    /// Where `clone` places repositories inside of a root.
+    pub layout: CloneLayout,
+
+    /// Shorthand prefixes for remotes. `gh:owner/repo` expands to the value of `gh`
+    /// followed by `owner/repo`.
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Default for CloningOptions {
+    fn default() -> Self {
+        Self {
+            layout: CloneLayout::default(),
+            aliases: BTreeMap::from([
+                (String::from("gh"), String::from("https://github.com/")),
+                (String::from("gl"), String::from("https://gitlab.com/")),
+            ]),
+        }
+    }
+}
+
+impl CloningOptions {
+    /// Expands a shorthand like `gh:owner/repo` into a full URL.
+    /// Remotes that do not start with a known alias are returned as is.
+    pub fn expand_remote(&self, remote: &str) -> String {
+        if let Some((prefix, rest)) = remote.split_once(':')
+            && !rest.starts_with("//")
+            && let Some(base) = self.aliases.get(prefix)
+        {
+            return format!("{base}{rest}");
+        }
+        remote.to_string()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GroupOptions {
+    /// How many directories deep projects are looked for, e.g. `3` for `clients/acme/api`.
+    pub max_depth: usize,
+
+    /// File names that mark a directory as a project, in addition to `.git`,
+    /// `.kanri.toml` and manifests of known build systems.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<String>,
+}
+
+impl Default for GroupOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 1,
+            markers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArchiveOptions {
+    /// Where archived projects are stored.
+    pub directory: PathBuf,
+    pub format: ArchiveFormat,
+
+    /// Names of directories skipped by `archive --exclude-artifacts`.
+    pub artifacts: Vec<String>,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            directory: platform::archive_dir(),
+            format: ArchiveFormat::default(),
+            artifacts: [
+                "target",
+                "node_modules",
+                "dist",
+                "build",
+                ".venv",
+                "__pycache__",
+                ".gradle",
+                ".zig-cache",
+                "zig-out",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
         }
     }
 }
@@ -125,7 +375,14 @@ pub struct ShellOptions {
 
 impl Default for ShellOptions {
     fn default() -> Self {
-        let program = platform::default_shell().to_string();
+        Self::for_program(&platform::default_shell())
+    }
+}
+
+impl ShellOptions {
+    /// Options for running commands with `program`, with the arguments it needs to run a command.
+    pub fn for_program(program: &str) -> Self {
+        let program = program.to_string();
         let args = match program.as_str() {
             "powershell.exe" | "powershell" | "pwsh.exe" | "pwsh" => {
                 vec!["-NoLogo".to_string(), "-Command".to_string()]
@@ -141,8 +398,86 @@ impl Default for ShellOptions {
 impl Config {
     pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
         let content = fs::read_to_string(&path).map_err(|_| ConfigError::FileNotFound)?;
-        toml::from_str::<Config>(&content)
-            .map_err(|e| ConfigError::BadConfiguration(format!("{e}")))
+        let mut config = toml::from_str::<Config>(&content)
+            .map_err(|e| ConfigError::BadConfiguration(format!("{e}")))?;
+        if config.default_editor.is_none() && config.editor.is_none() {
+            config.editor = Some(EditorOptions::default());
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks patterns of the configuration. Profiles are checked only when they are
+    /// used, so that a wrong profile name does not break commands like `config edit`.
+    fn validate(&self) -> Result<(), ConfigError> {
+        IgnoreRules::new(&self.ignore).map_err(|e| ConfigError::BadConfiguration(e.to_string()))?;
+
+        for rule in self.rules.iter() {
+            if let Some(pattern) = &rule.name {
+                Glob::new(pattern).map_err(|e| {
+                    ConfigError::BadConfiguration(format!(
+                        "invalid rule name '{pattern}': {}",
+                        e.kind()
+                    ))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The editor used when no profile is picked: the `default_editor` profile, or `[editor]`
+    /// in older configuration files.
+    pub fn default_editor(&self) -> Result<&EditorOptions, ConfigError> {
+        match &self.default_editor {
+            Some(name) => self
+                .editors
+                .get(name)
+                .ok_or_else(|| ConfigError::UnknownEditor(name.clone())),
+            None => self.editor.as_ref().ok_or(ConfigError::EditorNotConfigured),
+        }
+    }
+
+    /// Picks the editor for the project. The profile named by `with` wins, then the
+    /// profile of the first matching rule, and the default editor is used when nothing matches.
+    pub fn select_editor(
+        &self,
+        with: Option<&str>,
+        project: &ProjectTraits,
+    ) -> Result<&EditorOptions, ConfigError> {
+        let name = with.or_else(|| {
+            self.rules
+                .iter()
+                .filter(|rule| rule.matches(project.name, project.project_type, project.tags))
+                .find_map(|rule| rule.editor.as_deref())
+        });
+        match name {
+            Some(name) => self
+                .editors
+                .get(name)
+                .ok_or_else(|| ConfigError::UnknownEditor(name.to_string())),
+            None => self.default_editor(),
+        }
+    }
+
+    /// Picks the shell for the project the same way as [`Config::select_editor`].
+    pub fn select_shell(
+        &self,
+        with: Option<&str>,
+        project: &ProjectTraits,
+    ) -> Result<&ShellOptions, ConfigError> {
+        let name = with.or_else(|| {
+            self.rules
+                .iter()
+                .filter(|rule| rule.matches(project.name, project.project_type, project.tags))
+                .find_map(|rule| rule.shell.as_deref())
+        });
+        match name {
+            Some(name) => self
+                .shells
+                .get(name)
+                .ok_or_else(|| ConfigError::UnknownShell(name.to_string())),
+            None => Ok(&self.shell),
+        }
     }
 
     pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
@@ -154,6 +489,23 @@ impl Config {
         fs::write(path, content).map_err(|_| ConfigError::WriteFailed)
     }
 
+    /// Returns all project roots as `(name, path)` pairs.
+    /// The root from `projects_directory` always comes first and is named `default`.
+    pub fn roots(&self) -> Vec<(String, PathBuf)> {
+        let mut roots = vec![(
+            DEFAULT_ROOT.to_string(),
+            self.options.projects_directory.clone(),
+        )];
+        roots.extend(
+            self.options
+                .roots
+                .iter()
+                .filter(|(name, _)| name.as_str() != DEFAULT_ROOT)
+                .map(|(name, path)| (name.clone(), path.clone())),
+        );
+        roots
+    }
+
     pub fn reset(&mut self) {
         *self = Self::default();
     }
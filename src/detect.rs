@@ -0,0 +1,176 @@
+use std::{collections::HashSet, fmt, fs, path::Path};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Language a project is written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Rust,
+    Go,
+    Python,
+    #[value(name = "javascript", alias = "js")]
+    JavaScript,
+    #[value(name = "typescript", alias = "ts")]
+    TypeScript,
+    #[value(name = "csharp", alias = "cs")]
+    CSharp,
+    #[value(alias = "c", alias = "c++")]
+    Cpp,
+    Nix,
+    Zig,
+}
+
+impl Language {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Go => "Go",
+            Self::Python => "Python",
+            Self::JavaScript => "JavaScript",
+            Self::TypeScript => "TypeScript",
+            Self::CSharp => "C#",
+            Self::Cpp => "C/C++",
+            Self::Nix => "Nix",
+            Self::Zig => "Zig",
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Tool that builds a project or manages its dependencies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildSystem {
+    Cargo,
+    Go,
+    Pip,
+    Poetry,
+    Uv,
+    Npm,
+    Yarn,
+    Pnpm,
+    #[value(name = "msbuild")]
+    MSBuild,
+    #[value(name = "cmake")]
+    CMake,
+    Make,
+    Nix,
+    Zig,
+}
+
+impl BuildSystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cargo => "Cargo",
+            Self::Go => "Go",
+            Self::Pip => "pip",
+            Self::Poetry => "Poetry",
+            Self::Uv => "uv",
+            Self::Npm => "npm",
+            Self::Yarn => "Yarn",
+            Self::Pnpm => "pnpm",
+            Self::MSBuild => "MSBuild",
+            Self::CMake => "CMake",
+            Self::Make => "Make",
+            Self::Nix => "Nix",
+            Self::Zig => "Zig",
+        }
+    }
+}
+
+impl fmt::Display for BuildSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Languages and build systems of a project, guessed by the files in its root directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProjectType {
+    /// Languages in the order of priority, the main one first.
+    pub languages: Vec<Language>,
+    pub build_systems: Vec<BuildSystem>,
+}
+
+impl ProjectType {
+    pub fn detect(path: &Path) -> Self {
+        let Ok(entries) = fs::read_dir(path) else {
+            return Self::default();
+        };
+        let files: HashSet<String> = entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        let has = |name: &str| files.contains(name);
+
+        let mut kind = Self::default();
+        if has("Cargo.toml") {
+            kind.add(Language::Rust, BuildSystem::Cargo);
+        }
+        if has("go.mod") {
+            kind.add(Language::Go, BuildSystem::Go);
+        }
+        if has("pyproject.toml") {
+            let build = if has("uv.lock") {
+                BuildSystem::Uv
+            } else if has("poetry.lock") {
+                BuildSystem::Poetry
+            } else {
+                BuildSystem::Pip
+            };
+            kind.add(Language::Python, build);
+        }
+        if has("package.json") {
+            let language = if has("tsconfig.json") {
+                Language::TypeScript
+            } else {
+                Language::JavaScript
+            };
+            let build = if has("pnpm-lock.yaml") {
+                BuildSystem::Pnpm
+            } else if has("yarn.lock") {
+                BuildSystem::Yarn
+            } else {
+                BuildSystem::Npm
+            };
+            kind.add(language, build);
+        }
+        if files.iter().any(|file| file.ends_with(".csproj")) {
+            kind.add(Language::CSharp, BuildSystem::MSBuild);
+        }
+        if has("CMakeLists.txt") {
+            kind.add(Language::Cpp, BuildSystem::CMake);
+        }
+        if has("build.zig") {
+            kind.add(Language::Zig, BuildSystem::Zig);
+        }
+        if has("flake.nix") {
+            kind.add(Language::Nix, BuildSystem::Nix);
+        }
+        if has("Makefile") {
+            kind.build_systems.push(BuildSystem::Make);
+        }
+        kind
+    }
+
+    fn add(&mut self, language: Language, build_system: BuildSystem) {
+        self.languages.push(language);
+        self.build_systems.push(build_system);
+    }
+
+    /// The main language of the project.
+    pub fn language(&self) -> Option<Language> {
+        self.languages.first().copied()
+    }
+
+    pub fn has_language(&self, language: Language) -> bool {
+        self.languages.contains(&language)
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_BOUNDARY: i64 = 10;
+const BONUS_CONSECUTIVE: i64 = 8;
+const PENALTY_GAP: i64 = 2;
+const PENALTY_TYPO: i64 = 100;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Candidate {
+    pub name: String,
+    pub score: i64,
+}
+
+/// Scores how well `candidate` matches `query`. Returns `None` if it does not match at all.
+///
+/// Subsequence matches are always ranked above matches that need typo correction.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let original: Vec<char> = candidate.chars().collect();
+    let lowered: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    // Lowercasing may change the number of chars for some scripts.
+    if original.len() == lowered.len()
+        && let Some(score) = subsequence_score(&query, &original, &lowered)
+    {
+        return Some(score);
+    }
+
+    typo_score(&query, &lowered)
+}
+
+/// Returns all matching candidates ordered from the best to the worst match.
+/// Ties are broken by `weights` (e.g. frecency, highest first), then by the length
+/// of the candidate and then alphabetically.
+pub fn rank(query: &str, candidates: &[&str], weights: &HashMap<String, f64>) -> Vec<Candidate> {
+    let weight = |name: &str| weights.get(name).copied().unwrap_or_default();
+    let mut ranked: Vec<Candidate> = candidates
+        .iter()
+        .filter_map(|name| {
+            score(query, name).map(|score| Candidate {
+                name: name.to_string(),
+                score,
+            })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| weight(&b.name).total_cmp(&weight(&a.name)))
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ranked
+}
+
+fn subsequence_score(query: &[char], original: &[char], lowered: &[char]) -> Option<i64> {
+    let mut score = 0;
+    let mut query_idx = 0;
+    let mut previous: Option<usize> = None;
+
+    for (idx, ch) in lowered.iter().enumerate() {
+        if query_idx == query.len() {
+            break;
+        }
+        if *ch != query[query_idx] {
+            continue;
+        }
+
+        score += SCORE_MATCH;
+        if is_word_boundary(original, idx) {
+            score += BONUS_BOUNDARY;
+        }
+        match previous {
+            Some(prev) if prev + 1 == idx => score += BONUS_CONSECUTIVE,
+            Some(prev) => score -= (idx - prev - 1) as i64 * PENALTY_GAP,
+            None => score -= idx as i64,
+        }
+
+        previous = Some(idx);
+        query_idx += 1;
+    }
+
+    (query_idx == query.len()).then_some(score.max(1))
+}
+
+fn is_word_boundary(chars: &[char], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
+    }
+    let prev = chars[idx - 1];
+    let current = chars[idx];
+    matches!(prev, '-' | '_' | '.' | '/' | ' ')
+        || (prev.is_lowercase() && current.is_uppercase())
+        || (!prev.is_ascii_digit() && current.is_ascii_digit())
+}
+
+fn typo_score(query: &[char], candidate: &[char]) -> Option<i64> {
+    let allowed = match query.len() {
+        0..=2 => return None,
+        3..=7 => 1,
+        _ => 2,
+    };
+
+    let prefix = &candidate[..query.len().min(candidate.len())];
+    let distance = edit_distance(query, prefix).min(edit_distance(query, candidate));
+
+    (distance <= allowed).then(|| -(distance as i64) * PENALTY_TYPO)
+}
+
+/// Edit distance between two sequences of chars, where swapping two adjacent chars
+/// counts as a single edit (optimal string alignment distance).
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut before_previous: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 0..a.len() {
+        current[0] = i + 1;
+        for j in 0..b.len() {
+            let substitution = previous[j] + usize::from(a[i] != b[j]);
+            let mut distance = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                distance = distance.min(before_previous[j - 1] + 1);
+            }
+            current[j + 1] = distance;
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
@@ -0,0 +1,111 @@
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// State of a Git repository, read from `git status --porcelain=v2 --branch --show-stash`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitStatus {
+    /// Current branch, or `None` if HEAD is detached.
+    pub branch: Option<String>,
+
+    /// Number of commits the branch is ahead of and behind its upstream.
+    pub ahead: usize,
+    pub behind: usize,
+
+    /// Number of changed tracked files, staged or not.
+    pub changed: usize,
+    pub untracked: usize,
+    pub stashes: usize,
+}
+
+impl GitStatus {
+    pub fn is_dirty(&self) -> bool {
+        self.changed > 0 || self.untracked > 0
+    }
+
+    /// Parses the output of `git status --porcelain=v2 --branch --show-stash`.
+    pub fn parse(output: &str) -> Self {
+        let mut status = Self::default();
+        for line in output.lines() {
+            if let Some(head) = line.strip_prefix("# branch.head ") {
+                status.branch = (head != "(detached)").then(|| head.to_string());
+            } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+                for part in ab.split_whitespace() {
+                    if let Some(ahead) = part.strip_prefix('+') {
+                        status.ahead = ahead.parse().unwrap_or_default();
+                    } else if let Some(behind) = part.strip_prefix('-') {
+                        status.behind = behind.parse().unwrap_or_default();
+                    }
+                }
+            } else if let Some(stashes) = line.strip_prefix("# stash ") {
+                status.stashes = stashes.parse().unwrap_or_default();
+            } else if line.starts_with("1 ") || line.starts_with("2 ") || line.starts_with("u ") {
+                status.changed += 1;
+            } else if line.starts_with("? ") {
+                status.untracked += 1;
+            }
+        }
+        status
+    }
+}
+
+pub fn is_repository(path: &Path) -> bool {
+    path.join(".git").exists()
+}
+
+/// Reads the state of the repository at `path`.
+/// Returns `None` if it is not a repository or Git is not available.
+pub fn status(path: &Path) -> Option<GitStatus> {
+    if !is_repository(path) {
+        return None;
+    }
+
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "--branch", "--show-stash"])
+        .current_dir(path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(GitStatus::parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Returns all references of the repository with the commits they point to,
+/// so that a fetch or a pull can be checked for changes.
+pub fn refs(path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["for-each-ref", "--format=%(objectname) %(refname)"])
+        .current_dir(path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the URL of the `origin` remote of the repository at `path`.
+pub fn remote_url(path: &Path) -> Option<String> {
+    if !is_repository(path) {
+        return None;
+    }
+
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .current_dir(path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!url.is_empty()).then_some(url)
+}
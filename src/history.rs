@@ -0,0 +1,172 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Maximum number of visits kept in the history file.
+const MAX_VISITS: usize = 1000;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("Failed to read history file.")]
+    ReadFailed,
+
+    #[error("Failed to write history file.")]
+    WriteFailed,
+
+    #[error("Failed to format history data.")]
+    FormatFailed,
+
+    #[error("Error parsing history file.")]
+    BadHistory,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Visit {
+    pub project: String,
+    pub timestamp: u64,
+}
+
+/// Summary of visits for a single project.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub project: String,
+    pub visits: usize,
+    pub last_opened: u64,
+    pub score: f64,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct History {
+    visits: Vec<Visit>,
+}
+
+/// Current time as seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Weight of a single visit depending on how long ago it happened.
+fn visit_weight(age: u64) -> f64 {
+    match age {
+        a if a < HOUR => 4.0,
+        a if a < DAY => 2.0,
+        a if a < WEEK => 0.5,
+        _ => 0.25,
+    }
+}
+
+impl History {
+    /// Loads history from the file. A missing file results in empty history.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, HistoryError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|_| HistoryError::ReadFailed)?;
+        serde_json::from_str(&content).map_err(|_| HistoryError::BadHistory)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), HistoryError> {
+        let content = serde_json::to_string(self).map_err(|_| HistoryError::FormatFailed)?;
+        fs::write(path, content).map_err(|_| HistoryError::WriteFailed)
+    }
+
+    pub fn record(&mut self, project: &str, timestamp: u64) {
+        self.visits.push(Visit {
+            project: project.to_string(),
+            timestamp,
+        });
+        if self.visits.len() > MAX_VISITS {
+            let excess = self.visits.len() - MAX_VISITS;
+            self.visits.drain(..excess);
+        }
+    }
+
+    /// Moves all visits of the project to its new name.
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        self.visits
+            .iter_mut()
+            .filter(|visit| visit.project == old_name)
+            .for_each(|visit| visit.project = new_name.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+
+    /// Seeds empty history with `recent_project` from older configuration files,
+    /// so that `kanri open -` keeps working after an upgrade. Returns `true` if it was seeded.
+    pub fn migrate_recent(&mut self, recent_project: &str, timestamp: u64) -> bool {
+        if !self.is_empty() || recent_project.is_empty() {
+            return false;
+        }
+        self.record(recent_project, timestamp);
+        true
+    }
+
+    /// Frecency scores of all visited projects at the moment `now`.
+    pub fn scores(&self, now: u64) -> HashMap<String, f64> {
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for visit in self.visits.iter() {
+            *scores.entry(visit.project.clone()).or_default() +=
+                visit_weight(now.saturating_sub(visit.timestamp));
+        }
+        scores
+    }
+
+    /// Returns the `nth` most recently opened project, starting from 1.
+    pub fn recent(&self, nth: usize) -> Option<&str> {
+        let mut seen: Vec<&str> = Vec::new();
+        for visit in self.visits.iter().rev() {
+            if !seen.contains(&visit.project.as_str()) {
+                seen.push(&visit.project);
+                if seen.len() == nth {
+                    return Some(&visit.project);
+                }
+            }
+        }
+        None
+    }
+
+    /// Summaries of all visited projects, ordered by frecency.
+    pub fn entries(&self, now: u64) -> Vec<HistoryEntry> {
+        let mut entries: Vec<HistoryEntry> = Vec::new();
+        for visit in self.visits.iter() {
+            let weight = visit_weight(now.saturating_sub(visit.timestamp));
+            match entries.iter_mut().find(|e| e.project == visit.project) {
+                Some(entry) => {
+                    entry.visits += 1;
+                    entry.last_opened = entry.last_opened.max(visit.timestamp);
+                    entry.score += weight;
+                }
+                None => entries.push(HistoryEntry {
+                    project: visit.project.clone(),
+                    visits: 1,
+                    last_opened: visit.timestamp,
+                    score: weight,
+                }),
+            }
+        }
+
+        entries.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.last_opened.cmp(&a.last_opened))
+                .then_with(|| a.project.cmp(&b.project))
+        });
+        entries
+    }
+}
@@ -0,0 +1,103 @@
+use std::{fs, path::Path};
+
+use globset::{GlobBuilder, GlobMatcher};
+use thiserror::Error;
+
+/// Name of the file with ignore patterns in a root or a group.
+pub const IGNORE_FILE: &str = ".ignore";
+
+#[derive(Debug, Error)]
+pub enum IgnoreError {
+    #[error("Failed to read {0}: {1}.")]
+    ReadFailed(String, String),
+
+    #[error("Invalid ignore pattern '{0}': {1}.")]
+    BadPattern(String, String),
+}
+
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    matcher: GlobMatcher,
+    negated: bool,
+}
+
+/// Patterns with the syntax of `.gitignore` that hide directories from the library.
+///
+/// Patterns without a slash match at any depth, patterns with a slash are anchored
+/// to the directory of the `.ignore` file, `!` re-includes what an earlier pattern
+/// excluded, and lines starting with `#` are comments.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, IgnoreError> {
+        let mut rules = Vec::new();
+        for pattern in patterns {
+            if let Some(rule) = Self::parse_pattern(pattern.as_ref())? {
+                rules.push(rule);
+            }
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn parse(content: &str) -> Result<Self, IgnoreError> {
+        Self::new(&content.lines().collect::<Vec<_>>())
+    }
+
+    /// Loads the `.ignore` file from the directory. Returns `None` if there is no such file.
+    pub fn load(directory: &Path) -> Result<Option<Self>, IgnoreError> {
+        let path = directory.join(IGNORE_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| IgnoreError::ReadFailed(path.display().to_string(), e.to_string()))?;
+        Self::parse(&content).map(Some)
+    }
+
+    fn parse_pattern(line: &str) -> Result<Option<IgnoreRule>, IgnoreError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // Only directories are listed, so `dir/` means the same as `dir`.
+        let pattern = pattern.trim_end_matches('/');
+        let glob = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if pattern.contains('/') => pattern.to_string(),
+            None => format!("**/{pattern}"),
+        };
+        if glob.is_empty() {
+            return Ok(None);
+        }
+
+        let matcher = GlobBuilder::new(&glob)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| IgnoreError::BadPattern(line.to_string(), e.kind().to_string()))?
+            .compile_matcher();
+        Ok(Some(IgnoreRule { matcher, negated }))
+    }
+
+    /// Checks a path relative to the directory of the rules, e.g. `clients/acme`.
+    /// Returns `Some(true)` if it is ignored, `Some(false)` if it is re-included
+    /// and `None` if no pattern matches. The last matching pattern wins.
+    pub fn matched(&self, relative: &str) -> Option<bool> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matcher.is_match(relative))
+            .map(|rule| !rule.negated)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
@@ -0,0 +1,41 @@
+// Wrappers printed by `kanri shell-init`. They intercept `kanri cd <project>`, ask
+// `kanri path` for the directory of the project and change the directory of the
+// current shell. All other commands are passed to the executable as is.
+
+/// Wrapper for Bash and Zsh.
+pub const POSIX_INIT: &str = r#"kanri() {
+    if [ "$1" = "cd" ]; then
+        shift
+        local __kanri_dir
+        __kanri_dir="$(command kanri path --record "$@")" && builtin cd -- "$__kanri_dir"
+    else
+        command kanri "$@"
+    fi
+}
+"#;
+
+/// Wrapper for Fish.
+pub const FISH_INIT: &str = r#"function kanri
+    if test (count $argv) -gt 0; and test "$argv[1]" = "cd"
+        set -l __kanri_dir (command kanri path --record $argv[2..-1]); or return $status
+        builtin cd -- $__kanri_dir
+    else
+        command kanri $argv
+    end
+end
+"#;
+
+/// Wrapper for PowerShell.
+pub const PWSH_INIT: &str = r#"function kanri {
+    $__kanri = Get-Command -Name kanri -CommandType Application | Select-Object -First 1
+    if ($args.Count -gt 0 -and $args[0] -eq 'cd') {
+        $__kanri_rest = @($args | Select-Object -Skip 1)
+        $__kanri_dir = & $__kanri path --record @__kanri_rest
+        if ($LASTEXITCODE -eq 0 -and $__kanri_dir) {
+            Set-Location -LiteralPath $__kanri_dir
+        }
+    } else {
+        & $__kanri @args
+    }
+}
+"#;
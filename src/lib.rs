@@ -1,12 +1,25 @@
+mod archive;
 mod autocomplete;
 pub mod cli;
 pub mod commands;
+mod completions;
 pub mod config;
+mod detect;
+mod fuzzy;
+mod git;
+mod history;
+mod ignore;
+mod integration;
 mod library;
+mod manifest;
+mod metadata;
+pub mod output;
+mod parallel;
 pub mod platform;
 mod program;
 pub mod templates;
 pub mod terminal;
+mod trash;
 
 #[cfg(test)]
 mod tests;
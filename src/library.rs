@@ -1,11 +1,22 @@
 use std::{
     borrow::Cow,
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::program::{LaunchOptions, launch_program};
+use crate::{
+    archive::{ArchiveEntry, ArchiveFormat, Archives},
+    config::DEFAULT_ROOT,
+    detect::ProjectType,
+    ignore::IgnoreRules,
+    program::{LaunchOptions, launch_program},
+    trash::{Trash, TrashEntry},
+};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -36,8 +47,24 @@ pub enum LibraryError {
 
     #[error("An unexpected I/O error occurred: {0}.")]
     IoError(String),
+
+    #[error("Failed to move the project to the trash: {0}")]
+    TrashFailed(String),
+
+    #[error("Failed to archive the project: {0}")]
+    ArchiveFailed(String),
+
+    #[error("Root '{0}' is not defined in the configuration file.")]
+    RootNotFound(String),
 }
 
+/// How deep repositories are looked for under a host directory: the owner,
+/// an optional subgroup and the repository itself.
+const HOST_LAYOUT_DEPTH: usize = 3;
+
+/// Entries that make a directory a project, besides manifests of known build systems.
+const PROJECT_MARKERS: [&str; 2] = [".git", ".kanri.toml"];
+
 const SYSTEM_DIRECTORIES: [&str; 6] = [
     ".",
     "..",
@@ -47,24 +74,175 @@ const SYSTEM_DIRECTORIES: [&str; 6] = [
     ".Trash-1000",
 ];
 
+/// Where cloned repositories are placed inside of a root.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CloneLayout {
+    /// Directly in the root, e.g. `kanri`.
+    #[default]
+    #[serde(rename = "flat")]
+    Flat,
+
+    /// In directories named after the host and the owner, e.g. `github.com/kostya-zero/kanri`.
+    #[serde(rename = "host/owner/repo")]
+    HostOwnerRepo,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CloneOptions {
     pub remote: String,
     pub branch: Option<String>,
     pub name: Option<String>,
+
+    /// Root to clone into instead of the first one.
+    pub root: Option<String>,
+
+    /// Hide the output of Git and never ask for credentials.
+    pub quiet: bool,
+
+    pub layout: CloneLayout,
+
+    /// Create a shallow clone with this many commits.
+    pub depth: Option<u32>,
+    pub recurse_submodules: bool,
+    pub single_branch: bool,
+    pub bare: bool,
+
+    /// Check out only these directories, using `git sparse-checkout`.
+    pub sparse: Vec<String>,
+}
+
+impl CloneOptions {
+    /// Name of the directory the repository is cloned into, like `git clone` picks it.
+    pub fn directory_name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let remote = self.remote.trim_end_matches('/');
+        let remote = remote.strip_suffix(".git").unwrap_or(remote);
+        remote
+            .rsplit(['/', ':', '\\'])
+            .next()
+            .unwrap_or(remote)
+            .to_string()
+    }
+
+    /// Path of the project relative to the root, according to the layout.
+    /// Remotes without a host, like local paths, are always placed directly in the root.
+    pub fn relative_path(&self) -> PathBuf {
+        let nested = match self.layout {
+            CloneLayout::Flat => None,
+            CloneLayout::HostOwnerRepo => remote_segments(&self.remote),
+        };
+        match nested {
+            Some(mut segments) => {
+                if let Some(name) = &self.name
+                    && let Some(last) = segments.last_mut()
+                {
+                    *last = name.clone();
+                }
+                segments.iter().collect()
+            }
+            None => PathBuf::from(self.directory_name()),
+        }
+    }
+}
+
+/// Whether the name can be used as the directory of a project: a single path
+/// component that does not lead out of its parent directory.
+pub fn is_valid_directory_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(['/', '\\'])
+        && !SYSTEM_DIRECTORIES.contains(&name)
+        && matches!(
+            Path::new(name).components().collect::<Vec<_>>().as_slice(),
+            [std::path::Component::Normal(_)]
+        )
+}
+
+/// Whether the name looks like a host, e.g. `github.com`, and not like a directory
+/// that merely has a dot in its name, e.g. `v1.2` or `.config`.
+fn is_host_name(name: &str) -> bool {
+    let labels: Vec<&str> = name.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// Splits a remote URL into the host and the path segments, e.g.
+/// `git@github.com:owner/repo.git` into `github.com`, `owner` and `repo`.
+/// Returns `None` if the remote has no host or no owner.
+pub fn remote_segments(remote: &str) -> Option<Vec<String>> {
+    let (authority, path) = match remote.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        // SCP-like syntax, `user@host:path`. A colon after a slash belongs to a local path.
+        None => {
+            let (authority, path) = remote.split_once(':')?;
+            if authority.contains(['/', '\\']) || authority.len() < 2 {
+                return None;
+            }
+            (authority, path)
+        }
+    };
+
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    let mut segments = vec![host.to_string()];
+    segments.extend(
+        path.split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string),
+    );
+    let valid =
+        !host.is_empty() && segments.len() >= 3 && segments.iter().all(|s| s != "." && s != "..");
+    valid.then_some(segments)
+}
+
+#[derive(Debug, Clone)]
+pub struct Root {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl Root {
+    pub fn new(name: &str, path: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            path,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Project {
     name: Cow<'static, str>,
+    root: Cow<'static, str>,
     path: PathBuf,
+
+    /// Path of the project relative to its root, e.g. `api` or `github.com/owner/api`.
+    relative_name: String,
+
+    /// Detected on first use, see [`Project::project_type`].
+    project_type: OnceLock<ProjectType>,
 }
 
 impl Project {
     pub fn new(new_name: &str, new_path: PathBuf) -> Self {
         Self {
             name: Cow::Owned(new_name.to_string()),
+            root: Cow::Borrowed(DEFAULT_ROOT),
             path: new_path,
+            relative_name: new_name.to_string(),
+            project_type: OnceLock::new(),
         }
     }
 
@@ -72,10 +250,24 @@ impl Project {
         &self.name
     }
 
+    pub fn get_root(&self) -> &str {
+        &self.root
+    }
+
     pub fn get_path(&self) -> &str {
         self.path.to_str().unwrap_or_default()
     }
 
+    /// Path of the project relative to its root, e.g. `clients/acme/api`.
+    pub fn get_relative_name(&self) -> &str {
+        &self.relative_name
+    }
+
+    /// Name of the project prefixed with the name of its root, e.g. `work/api`.
+    pub fn get_qualified_name(&self) -> String {
+        format!("{}/{}", self.root, self.relative_name)
+    }
+
     pub fn is_empty(&self) -> bool {
         if let Ok(entries) = fs::read_dir(&self.path) {
             entries.count() == 0
@@ -83,34 +275,156 @@ impl Project {
             false
         }
     }
+
+    /// Time of the last change of the project directory or any entry directly in it,
+    /// as seconds since the Unix epoch.
+    pub fn modified(&self) -> Option<u64> {
+        let own = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        let entries = fs::read_dir(&self.path)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| entry.metadata().and_then(|m| m.modified()).ok());
+        own.into_iter().chain(entries).max().and_then(to_timestamp)
+    }
+
+    /// Time the project directory was created, if the file system records it.
+    pub fn created(&self) -> Option<u64> {
+        fs::metadata(&self.path)
+            .and_then(|m| m.created())
+            .ok()
+            .and_then(to_timestamp)
+    }
+
+    /// Languages and build systems of the project. Detected once and then cached.
+    pub fn project_type(&self) -> &ProjectType {
+        self.project_type
+            .get_or_init(|| ProjectType::detect(&self.path))
+    }
+
+    /// Total size of all files in the project in bytes. Symbolic links are not followed.
+    pub fn size(&self) -> u64 {
+        dir_size(&self.path)
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.name == name || self.get_qualified_name() == name
+    }
+}
+
+fn run_git(args: Vec<String>, cwd: Option<&Path>, quiet: bool) -> Result<(), LibraryError> {
+    let launch_options = LaunchOptions {
+        program: "git".to_string(),
+        args,
+        cwd: cwd.map(|path| path.to_string_lossy().into_owned()),
+        fork_mode: false,
+        quiet,
+        // Git must not wait for credentials when its output is hidden.
+        env: quiet.then(|| vec![("GIT_TERMINAL_PROMPT".to_string(), "0".to_string())]),
+        timeout: None,
+    };
+    launch_program(launch_options).map_err(|_| LibraryError::CloneFailed)
+}
+
+fn to_timestamp(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or_default(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// How projects are looked for inside of the roots.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub display_hidden: bool,
+
+    /// How many directories deep projects are looked for. Directories without project
+    /// markers are treated as groups and descended into. `0` and `1` disable grouping.
+    pub max_depth: usize,
+
+    /// Additional file names that mark a directory as a project.
+    pub markers: Vec<String>,
+
+    /// Patterns from the configuration that hide directories in every root.
+    pub ignore: IgnoreRules,
+
+    /// Whether repositories are cloned with the `host/owner/repo` layout, so that
+    /// directories named like hosts are looked into even without grouping.
+    pub host_layout: bool,
 }
 
 #[derive(Debug)]
 pub struct Library {
     projects: Vec<Project>,
-    base_path: PathBuf,
+    roots: Vec<Root>,
 }
 
 impl Library {
-    pub fn new(path: &PathBuf, display_hidden: bool) -> Result<Self, LibraryError> {
-        let base_path = PathBuf::from(path);
-        if !base_path.exists() || !base_path.is_dir() {
+    /// Creates a library that merges projects from all given roots.
+    /// The first root is used as a target for new and cloned projects.
+    pub fn new(roots: Vec<Root>, options: &ScanOptions) -> Result<Self, LibraryError> {
+        if roots.is_empty() {
             return Err(LibraryError::InvalidPath);
         }
-        let projects = Self::collect_projects(path, display_hidden)?;
 
-        Ok(Self {
-            projects,
-            base_path,
-        })
+        let mut projects = Vec::new();
+        for root in roots.iter() {
+            if !root.path.exists() || !root.path.is_dir() {
+                return Err(LibraryError::InvalidPath);
+            }
+            projects.extend(
+                Self::collect_projects(&root.path, options)?
+                    .into_iter()
+                    .map(|mut project| {
+                        project.root = Cow::Owned(root.name.clone());
+                        project
+                    }),
+            );
+        }
+
+        // Projects with the same name in different roots are addressed by qualified names.
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for project in projects.iter() {
+            *counts.entry(project.name.to_string()).or_default() += 1;
+        }
+        for project in projects.iter_mut() {
+            if counts
+                .get(project.name.as_ref())
+                .copied()
+                .unwrap_or_default()
+                > 1
+            {
+                project.name = Cow::Owned(project.get_qualified_name());
+            }
+        }
+
+        Ok(Self { projects, roots })
     }
 
     pub fn collect_projects(
         path: &PathBuf,
-        display_hidden: bool,
+        options: &ScanOptions,
     ) -> Result<Vec<Project>, LibraryError> {
         let dir_entries = fs::read_dir(path).map_err(|e| LibraryError::IoError(e.to_string()))?;
 
+        // Patterns from the configuration apply to every root, and `.ignore` files
+        // in the root override them.
+        let mut ignores = Vec::new();
+        if !options.ignore.is_empty() {
+            ignores.push((String::new(), options.ignore.clone()));
+        }
+        Self::push_ignore_file(&mut ignores, path, "")?;
+
         // Pre-allocate with estimated capacity
         let mut projects = Vec::with_capacity(10);
 
@@ -118,17 +432,97 @@ impl Library {
             let entry = entry.map_err(|e| LibraryError::IoError(e.to_string()))?;
             let name = entry.file_name().to_string_lossy().into_owned();
 
-            if Self::is_valid_project(&entry, &name, display_hidden) {
+            if !Self::is_valid_project(&entry, &name, options.display_hidden)
+                || Self::is_ignored(&ignores, &name)
+            {
+                continue;
+            }
+
+            // Directories without any projects inside are listed as projects themselves,
+            // so that new and unrecognized projects never disappear.
+            let nested = match Self::group_depth(&entry.path(), &name, options) {
+                0 => Vec::new(),
+                depth => Self::collect_nested(&entry.path(), &name, options, &ignores, depth)?,
+            };
+            if nested.is_empty() {
                 projects.push(Project::new(&name, entry.path()));
+            } else {
+                projects.extend(nested);
             }
         }
 
-        // Check if .ignore is present in this directory
-        if Path::new(path).join(".ignore").exists() {
-            let paths = Self::get_ignored_paths(path)?;
-            projects.retain(|project| !paths.iter().any(|p| p == project.get_name()));
+        Ok(projects)
+    }
+
+    /// How many levels below a directory in the root projects are looked for.
+    /// Returns `0` if the directory is a project itself.
+    fn group_depth(path: &Path, name: &str, options: &ScanOptions) -> usize {
+        let group_depth = options.max_depth.saturating_sub(1);
+        // Directories like `github.com` are created by the `host/owner/repo` layout.
+        let depth = if options.host_layout && is_host_name(name) {
+            group_depth.max(HOST_LAYOUT_DEPTH)
+        } else {
+            group_depth
+        };
+        if depth == 0 || Self::is_project_directory(path, &options.markers) {
+            0
+        } else {
+            depth
+        }
+    }
+
+    /// Whether the directory has a project marker: `.git`, `.kanri.toml`, a manifest
+    /// of a known build system or one of the additional `markers`.
+    pub fn is_project_directory(path: &Path, markers: &[String]) -> bool {
+        PROJECT_MARKERS
+            .iter()
+            .copied()
+            .chain(markers.iter().map(String::as_str))
+            .any(|marker| path.join(marker).exists())
+            || !ProjectType::detect(path).build_systems.is_empty()
+    }
+
+    /// Collects projects under `path` up to `depth` levels deep. Directories without
+    /// project markers are groups that are descended into, and are listed themselves
+    /// if there are no projects inside or the depth runs out, like in the root.
+    /// Names of the projects are prefixed with `prefix`, e.g. `clients/acme/api`.
+    fn collect_nested(
+        path: &Path,
+        prefix: &str,
+        options: &ScanOptions,
+        ignores: &[(String, IgnoreRules)],
+        depth: usize,
+    ) -> Result<Vec<Project>, LibraryError> {
+        let mut projects = Vec::new();
+        if depth == 0 {
+            return Ok(projects);
         }
+        let Ok(entries) = fs::read_dir(path) else {
+            return Ok(projects);
+        };
 
+        let mut ignores = ignores.to_vec();
+        Self::push_ignore_file(&mut ignores, path, prefix)?;
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let nested_name = format!("{prefix}/{name}");
+            if !Self::is_valid_project(&entry, &name, options.display_hidden)
+                || Self::is_ignored(&ignores, &nested_name)
+            {
+                continue;
+            }
+            let nested = if Self::is_project_directory(&entry.path(), &options.markers) {
+                Vec::new()
+            } else {
+                Self::collect_nested(&entry.path(), &nested_name, options, &ignores, depth - 1)?
+            };
+            if nested.is_empty() {
+                projects.push(Project::new(&nested_name, entry.path()));
+            } else {
+                projects.extend(nested);
+            }
+        }
         Ok(projects)
     }
 
@@ -141,71 +535,221 @@ impl Library {
             && !SYSTEM_DIRECTORIES.contains(&name)
     }
 
-    fn get_ignored_paths(base_path: &PathBuf) -> Result<Vec<String>, LibraryError> {
-        if !base_path.join(".ignore").exists() {
-            return Err(LibraryError::IoError(
-                "No .ignore file found in the specified path.".to_string(),
-            ));
+    /// Adds rules from the `.ignore` file of the directory, which is `prefix` relative to the root.
+    fn push_ignore_file(
+        ignores: &mut Vec<(String, IgnoreRules)>,
+        path: &Path,
+        prefix: &str,
+    ) -> Result<(), LibraryError> {
+        let rules = IgnoreRules::load(path).map_err(|e| LibraryError::IoError(e.to_string()))?;
+        if let Some(rules) = rules {
+            ignores.push((prefix.to_string(), rules));
         }
+        Ok(())
+    }
 
-        let ignore_content = fs::read_to_string(Path::new(base_path).join(".ignore"))
-            .map_err(|e| LibraryError::IoError(e.to_string()))?;
+    /// Checks a path relative to the root against all rules in effect, starting
+    /// with the `.ignore` file closest to it.
+    fn is_ignored(ignores: &[(String, IgnoreRules)], name: &str) -> bool {
+        ignores
+            .iter()
+            .rev()
+            .find_map(|(prefix, rules)| {
+                let relative = if prefix.is_empty() {
+                    name
+                } else {
+                    name.strip_prefix(prefix.as_str())?.strip_prefix('/')?
+                };
+                rules.matched(relative)
+            })
+            .unwrap_or(false)
+    }
 
-        let paths = Vec::from_iter(ignore_content.lines().filter_map(|line| {
-            let trimmed = line.trim();
-            if !trimmed.is_empty() && !trimmed.starts_with('#') {
-                Some(trimmed.to_string().replace('/', ""))
-            } else {
-                None
-            }
-        }));
+    /// Path the repository would be cloned into with these options.
+    pub fn clone_path(&self, options: &CloneOptions) -> Result<PathBuf, LibraryError> {
+        let base = match &options.root {
+            Some(name) => self
+                .roots
+                .iter()
+                .find(|root| root.name == *name)
+                .map(|root| root.path.as_path())
+                .ok_or_else(|| LibraryError::RootNotFound(name.clone()))?,
+            None => self.base_path(),
+        };
 
-        Ok(paths)
+        // Names may come from shared manifests, so they must not point outside of the root.
+        if options
+            .name
+            .as_deref()
+            .is_some_and(|name| !is_valid_directory_name(name))
+        {
+            return Err(LibraryError::InvalidProjectName);
+        }
+        let relative = options.relative_path();
+        if !relative
+            .iter()
+            .all(|part| is_valid_directory_name(&part.to_string_lossy()))
+        {
+            return Err(LibraryError::InvalidProjectName);
+        }
+        Ok(base.join(relative))
     }
 
-    pub fn clone(&self, options: &CloneOptions) -> Result<(), LibraryError> {
-        let mut args = vec!["clone".to_string(), options.remote.clone()];
-
-        if let Some(name) = &options.name {
-            args.push(name.to_owned());
+    /// Clones the repository into the first root, or the root from the options,
+    /// and returns the path to the new project. Nothing is left behind if cloning fails.
+    pub fn clone(&self, options: &CloneOptions) -> Result<PathBuf, LibraryError> {
+        let path = self.clone_path(options)?;
+        if path.exists() {
+            return Err(LibraryError::AlreadyExists);
         }
 
+        let mut args = vec!["clone".to_string()];
         if let Some(branch) = &options.branch {
             args.push("-b".to_string());
             args.push(branch.to_owned());
         }
+        if let Some(depth) = options.depth {
+            args.push(format!("--depth={depth}"));
+        }
+        if options.recurse_submodules {
+            args.push("--recurse-submodules".to_string());
+        }
+        if options.single_branch {
+            args.push("--single-branch".to_string());
+        }
+        if options.bare {
+            args.push("--bare".to_string());
+        }
+        if !options.sparse.is_empty() {
+            args.push("--sparse".to_string());
+        }
+        args.push(options.remote.clone());
+        args.push(path.to_string_lossy().into_owned());
 
-        let cwd = self.base_path.to_str().unwrap();
+        let result = run_git(args, None, options.quiet).and_then(|_| {
+            if options.sparse.is_empty() {
+                return Ok(());
+            }
+            let mut args = vec!["sparse-checkout".to_string(), "set".to_string()];
+            args.extend(options.sparse.iter().cloned());
+            run_git(args, Some(&path), options.quiet)
+        });
+        if let Err(e) = result {
+            self.remove_clone(&path);
+            return Err(e);
+        }
+        Ok(path)
+    }
 
-        let launch_options = LaunchOptions {
-            program: "git".to_string(),
-            args,
-            cwd: Some(cwd.to_string()),
-            fork_mode: false,
-            quiet: false,
-            env: None,
+    /// Removes a failed or unwanted clone, together with directories of the layout
+    /// that became empty because of it.
+    pub fn remove_clone(&self, path: &Path) {
+        let _ = fs::remove_dir_all(path);
+        let Some(root) = self.roots.iter().find(|root| path.starts_with(&root.path)) else {
+            return;
         };
+        let mut parent = path.parent();
+        while let Some(dir) = parent
+            && dir != root.path
+            && fs::remove_dir(dir).is_ok()
+        {
+            parent = dir.parent();
+        }
+    }
+
+    /// Directory where new and cloned projects are placed.
+    pub fn base_path(&self) -> &Path {
+        &self.roots[0].path
+    }
 
-        launch_program(launch_options).map_err(|_| LibraryError::CloneFailed)
+    pub fn get_roots(&self) -> &[Root] {
+        &self.roots
     }
 
-    pub fn create(&self, name: &str) -> Result<(), LibraryError> {
-        let path = &self.base_path.join(name);
+    /// Creates an empty project in the first root and returns its path.
+    pub fn create(&self, name: &str) -> Result<PathBuf, LibraryError> {
+        let path = self.base_path().join(name);
         if path.exists() {
             return Err(LibraryError::AlreadyExists);
         }
-        fs::create_dir(path).map_err(|e| LibraryError::IoError(e.to_string()))
+        fs::create_dir(&path).map_err(|e| LibraryError::IoError(e.to_string()))?;
+        Ok(path)
     }
 
     pub fn delete(&self, name: &str) -> Result<(), LibraryError> {
-        match fs::remove_dir_all(self.base_path.join(name)) {
+        let project = self.get(name)?;
+        match fs::remove_dir_all(&project.path) {
             Ok(_) => Ok(()),
             Err(_) => Err(LibraryError::FileSystemError),
         }
     }
 
+    /// Moves the project into the trash, so it can be restored later.
+    pub fn move_to_trash(
+        &self,
+        name: &str,
+        trash: &Trash,
+        now: u64,
+    ) -> Result<TrashEntry, LibraryError> {
+        let project = self.get(name)?;
+        trash
+            .put(project.get_root(), &project.path, now)
+            .map_err(|e| LibraryError::TrashFailed(e.to_string()))
+    }
+
+    /// Packs the project into an archive and removes it from the library.
+    /// Directories with names from `exclude` are not archived.
+    pub fn archive(
+        &self,
+        name: &str,
+        archives: &Archives,
+        format: ArchiveFormat,
+        exclude: &[String],
+        now: u64,
+    ) -> Result<ArchiveEntry, LibraryError> {
+        let project = self.get(name)?;
+        let entry = archives
+            .pack(
+                project.get_root(),
+                project.get_relative_name(),
+                &project.path,
+                format,
+                exclude,
+                now,
+            )
+            .map_err(|e| LibraryError::ArchiveFailed(e.to_string()))?;
+        fs::remove_dir_all(&project.path).map_err(|_| LibraryError::FileSystemError)?;
+        Ok(entry)
+    }
+
+    /// Unpacks an archived project back into its root, or into the first root
+    /// if the original one is not in the library. Returns the path to the project.
+    pub fn unarchive(&self, name: &str, archives: &Archives) -> Result<PathBuf, LibraryError> {
+        let entry = archives
+            .find(name)
+            .map_err(|e| LibraryError::ArchiveFailed(e.to_string()))?;
+        let root_path = self
+            .roots
+            .iter()
+            .find(|root| root.name == entry.root)
+            .map_or_else(|| self.base_path(), |root| root.path.as_path());
+
+        // The name comes from a file in the archive directory, so it must stay inside of the root.
+        if !entry.name.split('/').all(is_valid_directory_name) {
+            return Err(LibraryError::InvalidProjectName);
+        }
+        let path = root_path.join(&entry.name);
+        if path.exists() {
+            return Err(LibraryError::ProjectExists);
+        }
+        archives
+            .unpack(&entry, &path)
+            .map_err(|e| LibraryError::ArchiveFailed(e.to_string()))?;
+        Ok(path)
+    }
+
     pub fn contains(&self, name: &str) -> bool {
-        self.projects.iter().any(|x| x.name == *name)
+        self.projects.iter().any(|x| x.matches(name))
     }
 
     pub fn get_vec(&self) -> &[Project] {
@@ -219,7 +763,7 @@ impl Library {
     pub fn get(&self, name: &str) -> Result<&Project, LibraryError> {
         self.projects
             .iter()
-            .find(|x| x.name == name)
+            .find(|x| x.matches(name))
             .ok_or(LibraryError::ProjectNotFound)
     }
 
@@ -227,24 +771,31 @@ impl Library {
         self.projects.is_empty()
     }
 
-    pub fn rename(&self, old_name: &str, new_name: &str) -> Result<(), LibraryError> {
+    /// Renames the project and returns the new path to it.
+    pub fn rename(&self, old_name: &str, new_name: &str) -> Result<PathBuf, LibraryError> {
         if !self.contains(old_name) {
             return Err(LibraryError::ProjectNotFound);
         }
 
-        if self.contains(new_name) {
-            return Err(LibraryError::ProjectExists);
-        }
+        let project = self.get(old_name)?;
 
-        if SYSTEM_DIRECTORIES.contains(&new_name) {
+        if SYSTEM_DIRECTORIES.contains(&new_name) || new_name.contains(['/', '\\']) {
             return Err(LibraryError::InvalidProjectName);
         }
 
-        let old_path = Path::new(&self.base_path).join(old_name);
-        let new_path = Path::new(&self.base_path).join(new_name);
+        let old_path = project.path.clone();
+        let new_path = old_path
+            .parent()
+            .map(|parent| parent.join(new_name))
+            .ok_or(LibraryError::InvalidPath)?;
 
-        fs::rename(old_path, new_path)
+        // Names are only unique within a root, so check the directory itself.
+        if new_path.exists() {
+            return Err(LibraryError::ProjectExists);
+        }
+
+        fs::rename(old_path, &new_path)
             .map_err(|e| LibraryError::FailedToRename(e.kind().to_string()))?;
-        Ok(())
+        Ok(new_path)
     }
 }
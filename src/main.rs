@@ -1,12 +1,15 @@
 use std::process::exit;
 
 use anyhow::{Result, anyhow};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 use kanri::{
-    cli::{Cli, Commands, ConfigCommands, TemplatesCommands},
-    commands::{config, root, templates},
+    cli::{
+        COMPLETE_VAR, Cli, Commands, ConfigCommands, TagCommands, TemplatesCommands, TrashCommands,
+    },
+    commands::{config, list, manifest, metadata, root, status, sync, templates, trash},
     config::Config,
-    platform,
+    output, platform,
     templates::Templates,
     terminal::print_error,
 };
@@ -42,7 +45,13 @@ fn print_version() {
 }
 
 fn main() {
+    // Answers completion requests from the shell and exits.
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_VAR)
+        .complete();
+
     let cli = Cli::parse();
+    output::set_format(cli.format);
 
     if cli.version {
         print_version();
@@ -63,9 +72,31 @@ fn main() {
         Commands::New(args) => root::handle_new(args),
         Commands::Clone(args) => root::handle_clone(args),
         Commands::Open(args) => root::handle_open(args),
-        Commands::List(args) => root::handle_list(args),
+        Commands::List(args) => list::handle_list(args),
         Commands::Rename(args) => root::handle_rename(args),
         Commands::Remove(args) => root::handle_remove(args),
+        Commands::Tag { command } => match command {
+            TagCommands::Add(args) => metadata::handle_tag_add(args),
+            TagCommands::Remove(args) => metadata::handle_tag_remove(args),
+            TagCommands::List(args) => metadata::handle_tag_list(args),
+        },
+        Commands::Describe(args) => metadata::handle_describe(args),
+        Commands::Pin(args) => metadata::handle_pin(args),
+        Commands::Archive(args) => root::handle_archive(args),
+        Commands::Unarchive(args) => root::handle_unarchive(args),
+        Commands::Recent(args) => root::handle_recent(args),
+        Commands::Status(args) => status::handle_status(args),
+        Commands::Sync(args) => sync::handle_sync(args),
+        Commands::ExportManifest(args) => manifest::handle_export(args),
+        Commands::Path(args) => root::handle_path(args),
+        Commands::Cd(_) => root::handle_cd(),
+        Commands::ShellInit(args) => root::handle_shell_init(args),
+        Commands::Completions(args) => root::handle_completions(args),
+        Commands::Trash { command } => match command {
+            TrashCommands::List(args) => trash::handle_list(args),
+            TrashCommands::Restore(args) => trash::handle_restore(args),
+            TrashCommands::Purge(args) => trash::handle_purge(args),
+        },
         Commands::Templates { command } => match command {
             TemplatesCommands::New => templates::handle_new(),
             TemplatesCommands::List(args) => templates::handle_list(args),
@@ -0,0 +1,94 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::library::{CloneOptions, is_valid_directory_name};
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("Failed to read manifest file: {0}.")]
+    ReadFailed(String),
+
+    #[error("Failed to write manifest file: {0}.")]
+    WriteFailed(String),
+
+    #[error("Failed to format manifest.")]
+    FormatFailed,
+
+    #[error("Error parsing manifest file: {0}.")]
+    BadManifest(String),
+}
+
+/// A list of repositories to clone, e.g. to set up a new workstation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub repositories: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestEntry {
+    pub remote: String,
+
+    /// Directory name of the project. Taken from the remote if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// Root to clone into. The first root is used if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ManifestEntry {
+    pub fn clone_options(&self) -> CloneOptions {
+        CloneOptions {
+            remote: self.remote.clone(),
+            branch: self.branch.clone(),
+            name: self.name.clone(),
+            root: self.root.clone(),
+            quiet: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl Manifest {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| ManifestError::ReadFailed(e.to_string()))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ManifestError> {
+        let manifest: Self = toml::from_str(content)
+            .map_err(|e| ManifestError::BadManifest(e.message().to_string()))?;
+        for entry in manifest.repositories.iter() {
+            if let Some(name) = &entry.name
+                && !is_valid_directory_name(name)
+            {
+                return Err(ManifestError::BadManifest(format!(
+                    "name '{name}' of '{}' must be a single directory name",
+                    entry.remote
+                )));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn to_toml(&self) -> Result<String, ManifestError> {
+        toml::to_string(self).map_err(|_| ManifestError::FormatFailed)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ManifestError> {
+        fs::write(path, self.to_toml()?).map_err(|e| ManifestError::WriteFailed(e.to_string()))
+    }
+}
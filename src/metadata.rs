@@ -0,0 +1,184 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Name of the optional file with metadata inside of a project.
+pub const LOCAL_METADATA_FILE: &str = ".kanri.toml";
+
+#[derive(Debug, Error)]
+pub enum MetadataError {
+    #[error("Failed to read metadata file.")]
+    ReadFailed,
+
+    #[error("Failed to write metadata file.")]
+    WriteFailed,
+
+    #[error("Failed to format metadata.")]
+    FormatFailed,
+
+    #[error("Error parsing metadata file '{0}'.")]
+    BadMetadata(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectMetadata {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+
+    /// Time of creation with Kanri as seconds since the Unix epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+
+    /// Time the project was last opened as seconds since the Unix epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_opened: Option<u64>,
+}
+
+impl ProjectMetadata {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Adds the tag if the project does not have it yet. Returns `true` if it was added.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        if self.has_tag(tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        self.tags.sort();
+        true
+    }
+
+    /// Removes the tag. Returns `true` if the project had it.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let count = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() != count
+    }
+}
+
+/// Metadata of all projects, keyed by the path to the project.
+///
+/// Projects with a `.kanri.toml` file keep their metadata in that file instead.
+/// The time of the last opening is always kept in the central file, so opening
+/// a project does not change its files.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MetadataStore {
+    projects: BTreeMap<PathBuf, ProjectMetadata>,
+}
+
+impl MetadataStore {
+    /// Loads metadata from the file. A missing file results in an empty store.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MetadataError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|_| MetadataError::ReadFailed)?;
+        serde_json::from_str(&content)
+            .map_err(|_| MetadataError::BadMetadata(path.display().to_string()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), MetadataError> {
+        let content = serde_json::to_string(self).map_err(|_| MetadataError::FormatFailed)?;
+        fs::write(path, content).map_err(|_| MetadataError::WriteFailed)
+    }
+
+    pub fn get(&self, project_path: &Path) -> Result<ProjectMetadata, MetadataError> {
+        let central = self.get_central(project_path);
+
+        let local_path = project_path.join(LOCAL_METADATA_FILE);
+        if !local_path.exists() {
+            return Ok(central);
+        }
+
+        let content = fs::read_to_string(&local_path).map_err(|_| MetadataError::ReadFailed)?;
+        let mut local: ProjectMetadata = toml::from_str(&content)
+            .map_err(|_| MetadataError::BadMetadata(local_path.display().to_string()))?;
+        local.last_opened = central.last_opened;
+        Ok(local)
+    }
+
+    /// Metadata of the project from the central file only, without `.kanri.toml`.
+    pub fn get_central(&self, project_path: &Path) -> ProjectMetadata {
+        self.projects.get(project_path).cloned().unwrap_or_default()
+    }
+
+    /// Remembers when the project was opened. Only the central file is changed, so
+    /// a broken `.kanri.toml` does not get in the way. Returns `true` if the time changed.
+    pub fn set_last_opened(&mut self, project_path: &Path, timestamp: u64) -> bool {
+        let metadata = self.projects.entry(project_path.to_path_buf()).or_default();
+        if metadata.last_opened == Some(timestamp) {
+            return false;
+        }
+        metadata.last_opened = Some(timestamp);
+        true
+    }
+
+    /// Stores metadata of the project. The central file still has to be saved afterwards.
+    pub fn set(
+        &mut self,
+        project_path: &Path,
+        metadata: ProjectMetadata,
+    ) -> Result<(), MetadataError> {
+        let local_path = project_path.join(LOCAL_METADATA_FILE);
+        let central = if local_path.exists() {
+            let local = ProjectMetadata {
+                last_opened: None,
+                ..metadata.clone()
+            };
+            // Keep the file untouched, with its comments and formatting, if nothing changed.
+            let current = ProjectMetadata {
+                last_opened: None,
+                ..self.get(project_path)?
+            };
+            if local != current {
+                let content = toml::to_string(&local).map_err(|_| MetadataError::FormatFailed)?;
+                fs::write(&local_path, content).map_err(|_| MetadataError::WriteFailed)?;
+            }
+            ProjectMetadata {
+                last_opened: metadata.last_opened,
+                ..Default::default()
+            }
+        } else {
+            metadata
+        };
+
+        if central == ProjectMetadata::default() {
+            self.projects.remove(project_path);
+        } else {
+            self.projects.insert(project_path.to_path_buf(), central);
+        }
+        Ok(())
+    }
+
+    /// Changes metadata of the project with `update`.
+    pub fn update(
+        &mut self,
+        project_path: &Path,
+        update: impl FnOnce(&mut ProjectMetadata),
+    ) -> Result<(), MetadataError> {
+        let mut metadata = self.get(project_path)?;
+        update(&mut metadata);
+        self.set(project_path, metadata)
+    }
+
+    /// Moves metadata of the project to its new path.
+    pub fn rename(&mut self, old_path: &Path, new_path: &Path) {
+        if let Some(metadata) = self.projects.remove(old_path) {
+            self.projects.insert(new_path.to_path_buf(), metadata);
+        }
+    }
+}
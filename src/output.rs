@@ -0,0 +1,250 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::git::GitStatus;
+
+// Structured output of Kanri. The types below form the schema that is described
+// in `docs/OUTPUT.md`. Fields may be added, but existing ones must not change.
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum OutputError {
+    #[error("Failed to format output: {0}.")]
+    FormatFailed(String),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Text,
+
+    Json,
+
+    Yaml,
+}
+
+/// Sets the output format for the rest of the run. Only the first call has an effect.
+pub fn set_format(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Returns `true` if output must be printed as JSON or YAML instead of text.
+pub fn is_structured() -> bool {
+    format() != OutputFormat::Text
+}
+
+fn render<T: Serialize>(value: &T) -> Result<String, OutputError> {
+    match format() {
+        OutputFormat::Yaml => {
+            serde_yaml::to_string(value).map_err(|e| OutputError::FormatFailed(e.to_string()))
+        }
+        _ => serde_json::to_string_pretty(value)
+            .map(|json| json + "\n")
+            .map_err(|e| OutputError::FormatFailed(e.to_string())),
+    }
+}
+
+/// Prints the value to stdout in the selected structured format.
+pub fn print<T: Serialize>(value: &T) -> Result<(), OutputError> {
+    print!("{}", render(value)?);
+    Ok(())
+}
+
+/// Prints the error to stderr in the selected structured format.
+pub fn print_error(message: &str) {
+    let error = ErrorInfo {
+        error: ErrorDetails {
+            message: message.to_string(),
+        },
+    };
+    match render(&error) {
+        Ok(text) => eprint!("{text}"),
+        Err(_) => eprintln!("{message}"),
+    }
+}
+
+/// Prints the warning to stderr in the selected structured format.
+pub fn print_warning(message: &str) {
+    let warning = WarningInfo {
+        warning: WarningDetails {
+            message: message.to_string(),
+        },
+    };
+    match render(&warning) {
+        Ok(text) => eprint!("{text}"),
+        Err(_) => eprintln!("{message}"),
+    }
+}
+
+#[derive(Serialize)]
+pub struct ErrorInfo {
+    pub error: ErrorDetails,
+}
+
+#[derive(Serialize)]
+pub struct ErrorDetails {
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct WarningInfo {
+    pub warning: WarningDetails,
+}
+
+#[derive(Serialize)]
+pub struct WarningDetails {
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct PathInfo {
+    pub path: PathBuf,
+}
+
+impl PathInfo {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct GitInfo {
+    pub branch: Option<String>,
+    pub dirty: bool,
+    pub ahead: usize,
+    pub behind: usize,
+    pub changed: usize,
+    pub untracked: usize,
+    pub stashes: usize,
+}
+
+impl From<&GitStatus> for GitInfo {
+    fn from(status: &GitStatus) -> Self {
+        Self {
+            branch: status.branch.clone(),
+            dirty: status.is_dirty(),
+            ahead: status.ahead,
+            behind: status.behind,
+            changed: status.changed,
+            untracked: status.untracked,
+            stashes: status.stashes,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProjectInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub root: String,
+    pub tags: Vec<String>,
+    pub description: String,
+    pub pinned: bool,
+    pub language: Option<String>,
+    pub languages: Vec<String>,
+    pub build_systems: Vec<String>,
+    pub created: Option<u64>,
+    pub last_opened: Option<u64>,
+    pub modified: Option<u64>,
+    pub size: Option<u64>,
+    pub git: Option<GitInfo>,
+}
+
+#[derive(Serialize)]
+pub struct ProjectStatusInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub root: String,
+    pub git: GitInfo,
+}
+
+#[derive(Serialize)]
+pub struct SyncResultInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub root: String,
+    pub result: String,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ArchivedProjectInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub root: String,
+    pub format: String,
+    pub size: u64,
+    pub archived_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct TrashedProjectInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub root: String,
+    pub deleted_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct RecentProjectInfo {
+    pub name: String,
+    pub visits: usize,
+    pub last_opened: u64,
+    pub score: f64,
+}
+
+#[derive(Serialize)]
+pub struct TagInfo {
+    pub name: String,
+    pub projects: usize,
+}
+
+#[derive(Serialize)]
+pub struct TemplateSummary {
+    pub name: String,
+    pub kind: String,
+}
+
+#[derive(Serialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub kind: String,
+    pub skeleton: Option<PathBuf>,
+    pub variables: Vec<TemplateVariableInfo>,
+    pub steps: Vec<TemplateStepInfo>,
+}
+
+#[derive(Serialize)]
+pub struct TemplateVariableInfo {
+    pub name: String,
+    pub description: String,
+    pub default: Option<String>,
+    pub pattern: Option<String>,
+    pub choices: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct TemplateStepInfo {
+    pub command: String,
+    pub label: Option<String>,
+    pub cwd: Option<String>,
+    pub env: BTreeMap<String, String>,
+    pub timeout: Option<u64>,
+    pub continue_on_error: bool,
+    pub shell: Option<String>,
+}
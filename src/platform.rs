@@ -32,6 +32,24 @@ pub fn config_file() -> PathBuf {
 pub fn templates_file() -> PathBuf {
     config_dir().join("templates.json")
 }
+/// Directory with skeleton directories of templates.
+pub fn skeletons_dir() -> PathBuf {
+    config_dir().join("templates")
+}
+pub fn history_file() -> PathBuf {
+    config_dir().join("history.json")
+}
+pub fn metadata_file() -> PathBuf {
+    config_dir().join("metadata.json")
+}
+/// Default directory for archived projects.
+pub fn archive_dir() -> PathBuf {
+    config_dir().join("archive")
+}
+/// Directory where removed projects are kept.
+pub fn trash_dir() -> PathBuf {
+    config_dir().join("trash")
+}
 
 pub fn default_editor() -> Cow<'static, str> {
     if let Ok(v) = env::var("VISUAL").or_else(|_| env::var("EDITOR")) {
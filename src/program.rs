@@ -1,7 +1,9 @@
 use anyhow::Result;
 use std::{
-    io::ErrorKind,
-    process::{Command, Stdio},
+    io::{self, ErrorKind},
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 
@@ -19,6 +21,13 @@ pub enum ProgramError {
     #[error("Program exited with non-zero status: {0}")]
     NonZeroExitCode(i32),
 
+    #[error("Program did not finish in {0} seconds")]
+    TimedOut(u64),
+
+    /// Non-zero exit with the last line the program printed to stderr.
+    #[error("{0}")]
+    Failed(String),
+
     #[error("An unexpected error occurred: {0}")]
     UnexpectedError(String),
 }
@@ -31,6 +40,149 @@ pub struct LaunchOptions {
     pub fork_mode: bool,
     pub quiet: bool,
     pub env: Option<Vec<(String, String)>>,
+    /// Kill the program if it runs longer than this. Ignored in fork mode.
+    pub timeout: Option<Duration>,
+}
+
+/// How often a program with a timeout is checked for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+fn spawn_error(program: &str, e: io::Error) -> ProgramError {
+    match e.kind() {
+        ErrorKind::NotFound => ProgramError::ProgramNotFound(program.to_string()),
+        ErrorKind::PermissionDenied => ProgramError::NoPermission,
+        ErrorKind::Interrupted => ProgramError::ProcessInterrupted,
+        _ => ProgramError::UnexpectedError(e.to_string()),
+    }
+}
+
+/// Kills the child together with the processes it started, e.g. the real command
+/// behind `sh -c`. On Unix the child leads its own process group.
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // SAFETY: `killpg` only sends a signal and does not touch memory.
+        unsafe {
+            libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Gives the terminal back to Kanri when dropped, after a program in its own
+/// process group had it.
+#[cfg(unix)]
+struct TerminalGuard;
+
+#[cfg(unix)]
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // SAFETY: only the foreground process group of the terminal is changed.
+        // SIGTTOU is ignored meanwhile, because Kanri is in the background now.
+        unsafe {
+            let previous = libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+            libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp());
+            libc::signal(libc::SIGTTOU, previous);
+        }
+    }
+}
+
+/// Runs the command to completion, killing it and everything it started once
+/// `timeout` is exceeded. With `interactive`, the command uses the terminal of
+/// Kanri, so it can read input and receives Ctrl+C.
+fn wait_with_timeout(
+    cmd: &mut Command,
+    timeout: Duration,
+    interactive: bool,
+) -> Result<ExitStatus, ProgramError> {
+    #[cfg(unix)]
+    let _guard = {
+        use std::{io::IsTerminal, os::unix::process::CommandExt};
+
+        // The own process group lets the whole tree be killed, but a background group
+        // is stopped when it reads from the terminal, so it becomes the foreground one.
+        cmd.process_group(0);
+        let interactive = interactive && io::stdin().is_terminal();
+        if interactive {
+            // SAFETY: the closure only calls async-signal-safe functions.
+            unsafe {
+                cmd.pre_exec(|| {
+                    libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+                    libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpid());
+                    libc::signal(libc::SIGTTOU, libc::SIG_DFL);
+                    Ok(())
+                });
+            }
+        }
+        interactive.then_some(TerminalGuard)
+    };
+    #[cfg(not(unix))]
+    let _ = interactive;
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| spawn_error(&cmd.get_program().to_string_lossy(), e))?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(status),
+            Ok(None) if started.elapsed() >= timeout => {
+                kill_tree(&mut child);
+                return Err(ProgramError::TimedOut(timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(ProgramError::UnexpectedError(e.to_string())),
+        }
+    }
+}
+
+/// Runs the program without a terminal. If it fails, the error has the first line
+/// of its stderr that starts with `fatal:` or `error:`, like the reason printed by Git,
+/// or the last line otherwise. Fork mode and timeouts are ignored.
+pub fn launch_captured(options: LaunchOptions) -> Result<(), ProgramError> {
+    let mut cmd = Command::new(&options.program);
+    cmd.args(options.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    if let Some(cwd_path) = options.cwd {
+        cmd.current_dir(cwd_path);
+    }
+    if let Some(env) = options.env {
+        cmd.envs(env);
+    }
+
+    let output = cmd.output().map_err(|e| spawn_error(&options.program, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let reason = lines
+        .iter()
+        .find(|line| line.starts_with("fatal:") || line.starts_with("error:"))
+        .or(lines.last());
+    match reason {
+        Some(line) => Err(ProgramError::Failed(line.to_string())),
+        None => match output.status.code() {
+            Some(code) => Err(ProgramError::NonZeroExitCode(code)),
+            None => Err(ProgramError::ProcessInterrupted),
+        },
+    }
 }
 
 pub fn launch_program(options: LaunchOptions) -> Result<(), ProgramError> {
@@ -65,41 +217,20 @@ pub fn launch_program(options: LaunchOptions) -> Result<(), ProgramError> {
 
     if options.fork_mode {
         // In fork mode, we just spawn and don't wait for completion
-        let result = cmd.spawn();
-        if let Err(e) = result {
-            return match e.kind() {
-                ErrorKind::NotFound => {
-                    Err(ProgramError::ProgramNotFound(options.program.to_string()))
-                }
-                ErrorKind::PermissionDenied => Err(ProgramError::NoPermission),
-                ErrorKind::Interrupted => Err(ProgramError::ProcessInterrupted),
-                _ => Err(ProgramError::UnexpectedError(e.to_string())),
-            };
-        }
+        cmd.spawn().map_err(|e| spawn_error(&options.program, e))?;
     } else {
         // In blocking mode, we wait for completion and check exit status
-        let result = cmd.status();
-        match result {
-            Ok(status) => {
-                if !status.success() {
-                    // Handle non-zero exit codes
-                    if let Some(code) = status.code() {
-                        return Err(ProgramError::NonZeroExitCode(code));
-                    } else {
-                        // Process was terminated by signal (Unix only)
-                        return Err(ProgramError::ProcessInterrupted);
-                    }
-                }
-            }
-            Err(e) => {
-                return match e.kind() {
-                    ErrorKind::NotFound => {
-                        Err(ProgramError::ProgramNotFound(options.program.to_string()))
-                    }
-                    ErrorKind::PermissionDenied => Err(ProgramError::NoPermission),
-                    ErrorKind::Interrupted => Err(ProgramError::ProcessInterrupted),
-                    _ => Err(ProgramError::UnexpectedError(e.to_string())),
-                };
+        let status = match options.timeout {
+            Some(timeout) => wait_with_timeout(&mut cmd, timeout, !options.quiet)?,
+            None => cmd.status().map_err(|e| spawn_error(&options.program, e))?,
+        };
+        if !status.success() {
+            // Handle non-zero exit codes
+            if let Some(code) = status.code() {
+                return Err(ProgramError::NonZeroExitCode(code));
+            } else {
+                // Process was terminated by signal (Unix only)
+                return Err(ProgramError::ProcessInterrupted);
             }
         }
     }
@@ -1,10 +1,21 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::{config::ShellOptions, platform};
+
+/// Name of the variable that is always available and contains the name of the project.
+pub const PROJECT_VARIABLE: &str = "project";
+
 #[derive(Deserialize, Serialize, Clone, Default)]
-pub struct Templates(HashMap<String, Vec<String>>);
+pub struct Templates(HashMap<String, Template>);
 
 #[derive(Debug, Error, Deserialize)]
 pub enum TemplatesError {
@@ -25,6 +36,508 @@ pub enum TemplatesError {
 
     #[error("Commands in the template are empty.")]
     CommandsAreEmpty,
+
+    #[error("Template has no variable named '{0}'.")]
+    UnknownVariable(String),
+
+    #[error("No value provided for variable '{0}'.")]
+    MissingVariable(String),
+
+    #[error("Value '{1}' is not valid for variable '{0}'.")]
+    InvalidValue(String, String),
+
+    #[error("Variable '{0}' has an invalid pattern.")]
+    InvalidPattern(String),
+
+    #[error("Skeleton directory for template '{0}' not found.")]
+    SkeletonNotFound(String),
+
+    #[error("Failed to copy skeleton files: {0}.")]
+    SkeletonCopyFailed(String),
+
+    #[error("Shell is not configured in the configuration file.")]
+    ShellNotConfigured,
+
+    #[error("Directory '{0}' of a step must be relative to the project.")]
+    InvalidStepDirectory(String),
+
+    #[error("Skeleton file name '{0}' is not a valid name of a single file.")]
+    InvalidFileName(String),
+}
+
+/// What a template does besides running commands.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateKind {
+    /// Only runs commands.
+    #[default]
+    Commands,
+
+    /// Copies the skeleton directory `templates/<name>/` into the project before running commands.
+    Skeleton,
+}
+
+/// A template with variables and commands to run in a new project.
+/// Templates that are stored as a plain list of commands are loaded as well.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(from = "TemplateRepr")]
+pub struct Template {
+    pub kind: TemplateKind,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<TemplateVariable>,
+    pub commands: Vec<TemplateStep>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TemplateRepr {
+    Commands(Vec<TemplateStep>),
+    Full(TemplateFields),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateFields {
+    #[serde(default)]
+    kind: TemplateKind,
+    #[serde(default)]
+    variables: Vec<TemplateVariable>,
+    #[serde(default)]
+    commands: Vec<TemplateStep>,
+}
+
+impl From<TemplateRepr> for Template {
+    fn from(value: TemplateRepr) -> Self {
+        match value {
+            TemplateRepr::Commands(commands) => Self {
+                kind: TemplateKind::Commands,
+                variables: Vec::new(),
+                commands,
+            },
+            TemplateRepr::Full(fields) => Self {
+                kind: fields.kind,
+                variables: fields.variables,
+                commands: fields.commands,
+            },
+        }
+    }
+}
+
+/// A single command of a template. Steps that are stored as a plain string only set `command`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(from = "StepRepr", into = "StepRepr")]
+pub struct TemplateStep {
+    pub command: String,
+
+    /// Shown instead of the command while the step runs.
+    pub label: Option<String>,
+
+    /// Subdirectory of the project to run the command in. Created if it does not exist.
+    pub cwd: Option<String>,
+
+    /// Extra environment variables for the command.
+    pub env: BTreeMap<String, String>,
+
+    /// Maximum number of seconds the command is allowed to run.
+    pub timeout: Option<u64>,
+
+    /// Keep applying the template if the command fails.
+    pub continue_on_error: bool,
+
+    /// Shell program to use instead of the one from the configuration file.
+    pub shell: Option<String>,
+}
+
+impl From<&str> for TemplateStep {
+    fn from(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum StepRepr {
+    Command(String),
+    Full(StepFields),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct StepFields {
+    command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    continue_on_error: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shell: Option<String>,
+}
+
+impl From<StepRepr> for TemplateStep {
+    fn from(value: StepRepr) -> Self {
+        match value {
+            StepRepr::Command(command) => Self {
+                command,
+                ..Default::default()
+            },
+            StepRepr::Full(fields) => Self {
+                command: fields.command,
+                label: fields.label,
+                cwd: fields.cwd,
+                env: fields.env,
+                timeout: fields.timeout,
+                continue_on_error: fields.continue_on_error,
+                shell: fields.shell,
+            },
+        }
+    }
+}
+
+impl From<TemplateStep> for StepRepr {
+    fn from(value: TemplateStep) -> Self {
+        if value == TemplateStep::from(value.command.as_str()) {
+            return StepRepr::Command(value.command);
+        }
+        StepRepr::Full(StepFields {
+            command: value.command,
+            label: value.label,
+            cwd: value.cwd,
+            env: value.env,
+            timeout: value.timeout,
+            continue_on_error: value.continue_on_error,
+            shell: value.shell,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateVariable {
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// Regular expression that the whole value must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// List of allowed values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+}
+
+impl TemplateVariable {
+    pub fn validate(&self, value: &str) -> Result<(), TemplatesError> {
+        let invalid = || TemplatesError::InvalidValue(self.name.clone(), value.to_string());
+
+        if !self.choices.is_empty() && !self.choices.iter().any(|c| c == value) {
+            return Err(invalid());
+        }
+
+        if let Some(pattern) = &self.pattern {
+            let regex = Regex::new(&format!("^(?:{pattern})$"))
+                .map_err(|_| TemplatesError::InvalidPattern(self.name.clone()))?;
+            if !regex.is_match(value) {
+                return Err(invalid());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A single command of a template, ready to be launched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedStep {
+    pub label: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: PathBuf,
+    pub env: Vec<(String, String)>,
+    pub timeout: Option<Duration>,
+    pub continue_on_error: bool,
+}
+
+/// Everything that applying a template to a project would do, with all variables resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplatePlan {
+    pub project_path: PathBuf,
+    /// Directory to copy into the project before running the steps.
+    pub skeleton: Option<PathBuf>,
+    pub values: HashMap<String, String>,
+    pub steps: Vec<PlannedStep>,
+}
+
+impl Template {
+    /// Builds a plan for applying the template named `name` to the project at `project_path`.
+    /// `values` are the resolved variables; `project` is added if it is missing.
+    pub fn plan(
+        &self,
+        name: &str,
+        project_name: &str,
+        project_path: &Path,
+        values: &HashMap<String, String>,
+        shell: &ShellOptions,
+    ) -> Result<TemplatePlan, TemplatesError> {
+        let mut values = values.clone();
+        values
+            .entry(PROJECT_VARIABLE.to_string())
+            .or_insert_with(|| project_name.to_string());
+
+        let skeleton = match self.kind {
+            TemplateKind::Skeleton => {
+                let path = platform::skeletons_dir().join(name);
+                if !path.is_dir() {
+                    return Err(TemplatesError::SkeletonNotFound(name.to_string()));
+                }
+                Some(path)
+            }
+            TemplateKind::Commands => None,
+        };
+
+        if self.commands.iter().any(|step| step.shell.is_none()) && shell.program.is_empty() {
+            return Err(TemplatesError::ShellNotConfigured);
+        }
+
+        // Variables are prefixed, so that names like `path` or `home` do not replace
+        // variables of the system.
+        let mut env = vec![(String::from("ENJO_PROJECT"), project_name.to_string())];
+        env.extend(
+            values
+                .iter()
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .map(|(k, v)| (variable_env_name(k), v.clone())),
+        );
+
+        let steps = self
+            .commands
+            .iter()
+            .map(|step| {
+                let command = render(&step.command, &values)?;
+                let label = match &step.label {
+                    Some(label) => render(label, &values)?,
+                    None => command.clone(),
+                };
+
+                let cwd = match &step.cwd {
+                    Some(dir) => {
+                        let dir = render(dir, &values)?;
+                        let relative = Path::new(&dir);
+                        if !relative
+                            .components()
+                            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+                        {
+                            return Err(TemplatesError::InvalidStepDirectory(dir));
+                        }
+                        project_path.join(relative)
+                    }
+                    None => project_path.to_path_buf(),
+                };
+
+                let mut step_env = env.clone();
+                for (key, value) in step.env.iter() {
+                    step_env.push((key.clone(), render(value, &values)?));
+                }
+
+                let shell = match &step.shell {
+                    Some(program) => ShellOptions::for_program(program),
+                    None => shell.clone(),
+                };
+                let mut args = shell.args;
+                args.push(command);
+
+                Ok(PlannedStep {
+                    label,
+                    program: shell.program,
+                    args,
+                    cwd,
+                    env: step_env,
+                    timeout: step.timeout.map(Duration::from_secs),
+                    continue_on_error: step.continue_on_error,
+                })
+            })
+            .collect::<Result<Vec<_>, TemplatesError>>()?;
+
+        Ok(TemplatePlan {
+            project_path: project_path.to_path_buf(),
+            skeleton,
+            values,
+            steps,
+        })
+    }
+
+    /// Resolves values of all variables of the template. Values from `provided` take precedence,
+    /// then `ask` is called for the rest. If `ask` returns `None` or an empty string,
+    /// the default value of the variable is used.
+    pub fn resolve_variables(
+        &self,
+        provided: &HashMap<String, String>,
+        mut ask: impl FnMut(&TemplateVariable) -> Option<String>,
+    ) -> Result<HashMap<String, String>, TemplatesError> {
+        if let Some(unknown) = provided
+            .keys()
+            .find(|key| !self.variables.iter().any(|v| &v.name == *key))
+        {
+            return Err(TemplatesError::UnknownVariable(unknown.clone()));
+        }
+
+        let mut values = HashMap::new();
+        for variable in self.variables.iter() {
+            let value = match provided.get(&variable.name) {
+                Some(value) => value.clone(),
+                None => ask(variable)
+                    .filter(|value| !value.is_empty())
+                    .or_else(|| variable.default.clone())
+                    .ok_or_else(|| TemplatesError::MissingVariable(variable.name.clone()))?,
+            };
+            variable.validate(&value)?;
+            values.insert(variable.name.clone(), value);
+        }
+        Ok(values)
+    }
+}
+
+/// Name of the environment variable with the value of a template variable,
+/// e.g. `KANRI_VAR_AUTHOR_NAME` for `author-name`.
+pub fn variable_env_name(name: &str) -> String {
+    format!("KANRI_VAR_{}", name.to_ascii_uppercase().replace('-', "_"))
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Replaces `{{name}}` placeholders with values of variables.
+/// Whitespace inside the braces is ignored, so `{{ name }}` works too.
+/// Braces that do not contain a plain name (e.g. `${{ github.ref }}`) are left as is.
+pub fn render(input: &str, values: &HashMap<String, String>) -> Result<String, TemplatesError> {
+    substitute(input, values, true)
+}
+
+/// Like [`render`], but placeholders of unknown variables are left as is, so skeletons
+/// can contain templates of other tools, like `{{ message }}` in a Vue component.
+pub fn render_declared(input: &str, values: &HashMap<String, String>) -> String {
+    substitute(input, values, false).unwrap_or_else(|_| input.to_string())
+}
+
+fn substitute(
+    input: &str,
+    values: &HashMap<String, String>,
+    strict: bool,
+) -> Result<String, TemplatesError> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        let value = match values.get(name) {
+            Some(value) if is_variable_name(name) => value,
+            None if strict && is_variable_name(name) => {
+                return Err(TemplatesError::UnknownVariable(name.to_string()));
+            }
+            _ => {
+                output.push_str(&rest[..start + end + 2]);
+                rest = &rest[start + end + 2..];
+                continue;
+            }
+        };
+
+        output.push_str(&rest[..start]);
+        output.push_str(value);
+        rest = &rest[start + end + 2..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Copies the `source` directory into `target`, rendering names and contents of files.
+/// Files that are not valid UTF-8 are copied as is.
+pub fn render_tree(
+    source: &Path,
+    target: &Path,
+    values: &HashMap<String, String>,
+) -> Result<(), TemplatesError> {
+    let copy_failed = |e: std::io::Error| TemplatesError::SkeletonCopyFailed(e.to_string());
+    fs::create_dir_all(target).map_err(copy_failed)?;
+
+    for entry in fs::read_dir(source).map_err(copy_failed)? {
+        let entry = entry.map_err(copy_failed)?;
+        let file_name = render_declared(&entry.file_name().to_string_lossy(), values);
+        // Names come from values of variables, which must not lead out of the project.
+        if file_name.is_empty()
+            || file_name == "."
+            || file_name == ".."
+            || file_name.contains(['/', '\\'])
+        {
+            return Err(TemplatesError::InvalidFileName(file_name));
+        }
+        let source_path = entry.path();
+        let target_path = target.join(file_name);
+
+        let file_type = entry.file_type().map_err(copy_failed)?;
+        if file_type.is_symlink() {
+            copy_symlink(&source_path, &target_path).map_err(copy_failed)?;
+            continue;
+        }
+        if file_type.is_dir() {
+            render_tree(&source_path, &target_path, values)?;
+            continue;
+        }
+
+        let content = fs::read(&source_path).map_err(copy_failed)?;
+        match String::from_utf8(content) {
+            Ok(text) => fs::write(&target_path, render_declared(&text, values)),
+            Err(e) => fs::write(&target_path, e.into_bytes()),
+        }
+        .map_err(copy_failed)?;
+
+        let permissions = fs::metadata(&source_path)
+            .map_err(copy_failed)?
+            .permissions();
+        fs::set_permissions(&target_path, permissions).map_err(copy_failed)?;
+    }
+
+    Ok(())
+}
+
+/// Creates the same symbolic link at `target`. The link is not followed, so links
+/// to directories are not copied twice and cannot loop.
+fn copy_symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    let link = fs::read_link(source)?;
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(link, target)
+    }
+    #[cfg(windows)]
+    {
+        if fs::metadata(source).is_ok_and(|m| m.is_dir()) {
+            std::os::windows::fs::symlink_dir(link, target)
+        } else {
+            std::os::windows::fs::symlink_file(link, target)
+        }
+    }
 }
 
 impl Templates {
@@ -45,11 +558,18 @@ impl Templates {
             return Err(TemplatesError::CommandsAreEmpty);
         }
 
-        self.0.insert(name.to_string(), commands);
+        self.0.insert(
+            name.to_string(),
+            Template {
+                kind: TemplateKind::Commands,
+                variables: Vec::new(),
+                commands: commands.iter().map(|cmd| cmd.as_str().into()).collect(),
+            },
+        );
         Ok(())
     }
 
-    pub fn get_template(&self, name: &str) -> Option<&Vec<String>> {
+    pub fn get_template(&self, name: &str) -> Option<&Template> {
         self.0.get(name)
     }
 
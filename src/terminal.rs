@@ -1,19 +1,33 @@
 use colored::Colorize;
 use dialoguer::{
-    Confirm, Input,
+    Confirm, FuzzySelect, Input, Select,
     console::{Style, style},
     theme::{ColorfulTheme, Theme},
 };
 use indicatif::ProgressBar;
 
+use crate::output;
+
 pub fn print_done(msg: &str) {
     println!(" {} {}", "✓".bold().green(), msg)
 }
 
 pub fn print_error(msg: &str) {
+    if output::is_structured() {
+        output::print_error(msg);
+        return;
+    }
     eprintln!(" {}: {}", "Error".red().bold(), msg);
 }
 
+pub fn print_warning(msg: &str) {
+    if output::is_structured() {
+        output::print_warning(msg);
+        return;
+    }
+    eprintln!(" {}: {}", "Warning".yellow().bold(), msg);
+}
+
 pub fn print_progress(msg: &str, current: i8, total: i8) {
     println!("{} {}", format!("[{current}/{total}]").white().bold(), msg);
 }
@@ -22,6 +36,33 @@ pub fn print_title(msg: &str) {
     println!("{}", msg.bold().underline());
 }
 
+/// Formats the time passed since `timestamp` as a short human readable string.
+pub fn format_elapsed(timestamp: u64, now: u64) -> String {
+    let elapsed = now.saturating_sub(timestamp);
+    match elapsed {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", elapsed / 60),
+        3600..86400 => format!("{} h ago", elapsed / 3600),
+        _ => format!("{} d ago", elapsed / 86400),
+    }
+}
+
+/// Formats a number of bytes as a human readable size, e.g. `12.3 MB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
 fn get_dialog_theme() -> impl Theme {
     ColorfulTheme {
         prompt_prefix: style(" ?".to_string()).for_stdout().cyan(),
@@ -51,6 +92,44 @@ pub fn ask_string_dialog(question: &str) -> String {
         .unwrap()
 }
 
+/// Asks for a string with a default value, repeating the question until `validate` accepts it.
+pub fn ask_validated_dialog(
+    question: &str,
+    default: &str,
+    validate: impl FnMut(&String) -> Result<(), String>,
+) -> String {
+    Input::<String>::with_theme(&get_dialog_theme())
+        .with_prompt(question)
+        .default(default.to_string())
+        .show_default(!default.is_empty())
+        .allow_empty(true)
+        .validate_with(validate)
+        .report(false)
+        .interact_text()
+        .unwrap()
+}
+
+pub fn ask_select_dialog(question: &str, items: &[String], default: usize) -> usize {
+    Select::with_theme(&get_dialog_theme())
+        .with_prompt(question)
+        .items(items)
+        .default(default)
+        .report(false)
+        .interact()
+        .unwrap()
+}
+
+/// Shows a filter-as-you-type list. Returns the index of the selected item or `None` if canceled.
+pub fn pick_dialog(prompt: &str, items: &[String]) -> Option<usize> {
+    FuzzySelect::with_theme(&get_dialog_theme())
+        .with_prompt(prompt)
+        .items(items)
+        .default(0)
+        .report(false)
+        .interact_opt()
+        .unwrap()
+}
+
 pub fn generate_progress() -> ProgressBar {
     ProgressBar::new_spinner().with_style(
         indicatif::ProgressStyle::with_template(" {spinner:.green} {msg}")
@@ -1,10 +1,24 @@
+mod test_archive;
 mod test_autocomplete;
+mod test_config;
+mod test_fuzzy;
+mod test_git;
+mod test_history;
+mod test_ignore;
 mod test_library;
+mod test_manifest;
+mod test_metadata;
+mod test_output;
+mod test_parallel;
+mod test_program;
 mod test_project;
+mod test_templates;
+mod test_trash;
 
 use std::path::Path;
 
 use super::*;
+use library::Root;
 use tempfile::TempDir;
 
 struct TestContext {
@@ -21,3 +35,7 @@ impl TestContext {
         self.temp_dir.path()
     }
 }
+
+fn single_root(path: &Path) -> Vec<Root> {
+    vec![Root::new(config::DEFAULT_ROOT, path.to_path_buf())]
+}
@@ -0,0 +1,133 @@
+use std::fs;
+
+use crate::{
+    archive::{ArchiveError, ArchiveFormat, Archives},
+    library::{Library, LibraryError, Root, ScanOptions},
+    tests::{TestContext, single_root},
+};
+
+fn create_project(context: &TestContext) {
+    let project = context.path().join("bookshelf");
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::create_dir_all(project.join("target/debug")).unwrap();
+    fs::write(project.join("src/main.rs"), "fn main() {}").unwrap();
+    fs::write(project.join("target/debug/bookshelf"), "binary").unwrap();
+}
+
+#[test]
+fn test_archive_and_unarchive() {
+    for format in [ArchiveFormat::TarZst, ArchiveFormat::TarGz] {
+        let projects = TestContext::setup();
+        let storage = TestContext::setup();
+        create_project(&projects);
+
+        let library = Library::new(single_root(projects.path()), &ScanOptions::default()).unwrap();
+        let archives = Archives::new(storage.path().join("archive"));
+        let exclude = vec![String::from("target")];
+        let entry = library
+            .archive("bookshelf", &archives, format, &exclude, 100)
+            .unwrap();
+
+        assert!(!projects.path().join("bookshelf").exists());
+        assert!(entry.size > 0);
+
+        let entries = archives.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "bookshelf");
+        assert_eq!(entries[0].format, format);
+        assert_eq!(entries[0].archived_at, 100);
+        assert_eq!(entries[0].size, entry.size);
+
+        let library = Library::new(single_root(projects.path()), &ScanOptions::default()).unwrap();
+        let path = library.unarchive("bookshelf", &archives).unwrap();
+        assert_eq!(path, projects.path().join("bookshelf"));
+        assert_eq!(
+            fs::read_to_string(path.join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(!path.join("target").exists());
+        assert!(archives.entries().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn test_archive_conflicts() {
+    let projects = TestContext::setup();
+    let storage = TestContext::setup();
+    create_project(&projects);
+
+    let library = Library::new(single_root(projects.path()), &ScanOptions::default()).unwrap();
+    let archives = Archives::new(storage.path().to_path_buf());
+    library
+        .archive("bookshelf", &archives, ArchiveFormat::TarZst, &[], 100)
+        .unwrap();
+
+    // A new project with the same name can not be archived over the old one.
+    create_project(&projects);
+    assert!(matches!(
+        archives.pack(
+            "default",
+            "bookshelf",
+            &projects.path().join("bookshelf"),
+            ArchiveFormat::TarGz,
+            &[],
+            200
+        ),
+        Err(ArchiveError::AlreadyArchived(_))
+    ));
+
+    // And the archived one is not unpacked over the new one.
+    let library = Library::new(single_root(projects.path()), &ScanOptions::default()).unwrap();
+    assert!(matches!(
+        library.unarchive("bookshelf", &archives),
+        Err(LibraryError::ProjectExists)
+    ));
+    assert_eq!(archives.entries().unwrap().len(), 1);
+}
+
+#[test]
+fn test_archive_nested_and_same_names() {
+    let work = TestContext::setup();
+    let oss = TestContext::setup();
+    let storage = TestContext::setup();
+    for root in [&work, &oss] {
+        fs::create_dir_all(root.path().join("clients/acme/api/.git")).unwrap();
+    }
+    let roots = || {
+        vec![
+            Root::new("work", work.path().to_path_buf()),
+            Root::new("oss", oss.path().to_path_buf()),
+        ]
+    };
+    let options = ScanOptions {
+        max_depth: 3,
+        ..Default::default()
+    };
+
+    let library = Library::new(roots(), &options).unwrap();
+    let archives = Archives::new(storage.path().to_path_buf());
+    for name in ["work/clients/acme/api", "oss/clients/acme/api"] {
+        library
+            .archive(name, &archives, ArchiveFormat::TarGz, &[], 100)
+            .unwrap();
+    }
+    let entries = archives.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry.name == "clients/acme/api"));
+
+    let library = Library::new(roots(), &options).unwrap();
+    assert!(matches!(
+        library.unarchive("clients/acme/api", &archives),
+        Err(LibraryError::ArchiveFailed(_))
+    ));
+    let path = library
+        .unarchive("oss/clients/acme/api", &archives)
+        .unwrap();
+    assert_eq!(path, oss.path().join("clients/acme/api"));
+    assert!(path.join(".git").is_dir());
+
+    // With one project left, the short name is enough.
+    let path = library.unarchive("clients/acme/api", &archives).unwrap();
+    assert_eq!(path, work.path().join("clients/acme/api"));
+    assert!(archives.entries().unwrap().is_empty());
+}
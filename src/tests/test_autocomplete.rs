@@ -1,13 +1,15 @@
-use crate::autocomplete::{CompletionResult, suggest_completion};
+use std::collections::HashMap;
+
+use crate::autocomplete::{CompletionResult, MatchPolicy, needs_confirmation, suggest_completion};
 
 #[test]
 pub fn test_autocomplete_found_similar() {
     let words: Vec<&str> = vec!["apple", "orange", "watermelon"];
 
-    let result = suggest_completion("ap", words);
+    let result = suggest_completion("wtrmln", words, &HashMap::new());
     assert_eq!(
         result,
-        CompletionResult::FoundSimilar(String::from("apple"))
+        CompletionResult::FoundSimilar(String::from("watermelon"))
     )
 }
 
@@ -15,7 +17,7 @@ pub fn test_autocomplete_found_similar() {
 pub fn test_autocomplete_found() {
     let words: Vec<&str> = vec!["apple", "orange", "watermelon"];
 
-    let result = suggest_completion("apple", words);
+    let result = suggest_completion("apple", words, &HashMap::new());
     assert_eq!(result, CompletionResult::Found)
 }
 
@@ -23,6 +25,56 @@ pub fn test_autocomplete_found() {
 pub fn test_autocomplete_nothing() {
     let words: Vec<&str> = vec!["apple", "orange", "watermelon"];
 
-    let result = suggest_completion("enjo", words);
+    let result = suggest_completion("enjo", words, &HashMap::new());
     assert_eq!(result, CompletionResult::Nothing)
 }
+
+#[test]
+pub fn test_autocomplete_last_segment() {
+    let words: Vec<&str> = vec!["clients/acme/api", "clients/acme/web", "apis"];
+
+    let result = suggest_completion("api", words, &HashMap::new());
+    assert_eq!(
+        result,
+        CompletionResult::FoundUnique(String::from("clients/acme/api"))
+    )
+}
+
+#[test]
+pub fn test_autocomplete_unique_prefix() {
+    let words: Vec<&str> = vec!["apple", "apricot", "orange"];
+
+    let result = suggest_completion("or", words.clone(), &HashMap::new());
+    assert_eq!(
+        result,
+        CompletionResult::FoundUnique(String::from("orange"))
+    );
+    assert!(!needs_confirmation(&result, true, MatchPolicy::Lenient));
+    assert!(needs_confirmation(&result, false, MatchPolicy::Lenient));
+
+    // An ambiguous prefix falls back to a fuzzy match.
+    let result = suggest_completion("ap", words, &HashMap::new());
+    assert!(matches!(result, CompletionResult::FoundSimilar(_)));
+}
+
+#[test]
+pub fn test_autocomplete_remove_needs_confirmation() {
+    let words: Vec<&str> = vec!["bookshelf", "kanri", "web"];
+
+    // `kanri rm bk` must never remove `bookshelf` without asking.
+    let result = suggest_completion("bk", words.clone(), &HashMap::new());
+    assert_eq!(
+        result,
+        CompletionResult::FoundSimilar(String::from("bookshelf"))
+    );
+    assert!(needs_confirmation(&result, true, MatchPolicy::Strict));
+    assert!(needs_confirmation(&result, true, MatchPolicy::Lenient));
+
+    let result = suggest_completion("book", words, &HashMap::new());
+    assert!(needs_confirmation(&result, true, MatchPolicy::Strict));
+    assert!(!needs_confirmation(
+        &CompletionResult::Found,
+        true,
+        MatchPolicy::Strict
+    ));
+}
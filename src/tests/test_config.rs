@@ -0,0 +1,148 @@
+use std::fs;
+
+use super::TestContext;
+use crate::{
+    config::{CloningOptions, Config, ConfigError, EditorOptions, OpenRule, ProjectTraits},
+    detect::{Language, ProjectType},
+};
+
+fn editor(program: &str) -> EditorOptions {
+    EditorOptions {
+        program: program.to_string(),
+        fork_mode: false,
+        args: Vec::new(),
+    }
+}
+
+fn config_with_rules() -> Config {
+    Config {
+        default_editor: Some(String::from("nvim")),
+        editors: [("nvim", "nvim"), ("code", "code"), ("rider", "rider")]
+            .into_iter()
+            .map(|(name, program)| (name.to_string(), editor(program)))
+            .collect(),
+        rules: vec![
+            OpenRule {
+                name: Some(String::from("unity-*")),
+                editor: Some(String::from("rider")),
+                ..Default::default()
+            },
+            OpenRule {
+                language: Some(Language::Rust),
+                tag: Some(String::from("work")),
+                editor: Some(String::from("code")),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_select_editor_by_rules() {
+    let config = config_with_rules();
+    let rust = ProjectType {
+        languages: vec![Language::Rust],
+        build_systems: Vec::new(),
+    };
+    let work = vec![String::from("work")];
+    let select = |name, project_type, tags| {
+        let traits = ProjectTraits {
+            name,
+            project_type,
+            tags,
+        };
+        config.select_editor(None, &traits).unwrap().program.clone()
+    };
+
+    assert_eq!(select("api", &rust, &work), "code");
+    assert_eq!(select("api", &rust, &[]), "nvim");
+    assert_eq!(select("unity-game", &rust, &work), "rider");
+    assert_eq!(select("notes", &ProjectType::default(), &work), "nvim");
+}
+
+#[test]
+fn test_select_editor_with_profile() {
+    let config = config_with_rules();
+    let traits = ProjectTraits {
+        name: "unity-game",
+        project_type: &ProjectType::default(),
+        tags: &[],
+    };
+
+    let selected = config.select_editor(Some("code"), &traits).unwrap();
+    assert_eq!(selected.program, "code");
+    assert!(matches!(
+        config.select_editor(Some("emacs"), &traits),
+        Err(ConfigError::UnknownEditor(_))
+    ));
+}
+
+#[test]
+fn test_default_config_has_editor_profile() {
+    let config = Config::default();
+    let name = config.default_editor.clone().unwrap();
+    assert!(config.editors.contains_key(&name));
+    assert!(config.editor.is_none());
+    assert!(config.default_editor().is_ok());
+}
+
+#[test]
+fn test_load_legacy_editor() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    fs::write(&path, "[editor]\nprogram = \"hx\"\n").unwrap();
+
+    let config = Config::load(&path).unwrap();
+    assert_eq!(config.default_editor, None);
+    assert_eq!(config.default_editor().unwrap().program, "hx");
+
+    // Unknown profiles are reported when they are used, not when the file is loaded.
+    fs::write(&path, "default_editor = \"code\"\n").unwrap();
+    let config = Config::load(&path).unwrap();
+    assert!(matches!(
+        config.default_editor(),
+        Err(ConfigError::UnknownEditor(_))
+    ));
+}
+
+#[test]
+fn test_expand_remote() {
+    let mut options = CloningOptions::default();
+    options
+        .aliases
+        .insert(String::from("work"), String::from("git@git.work.com:"));
+
+    assert_eq!(
+        options.expand_remote("gh:kostya-zero/kanri"),
+        "https://github.com/kostya-zero/kanri"
+    );
+    assert_eq!(
+        options.expand_remote("work:team/api.git"),
+        "git@git.work.com:team/api.git"
+    );
+    assert_eq!(
+        options.expand_remote("git@github.com:kostya-zero/kanri.git"),
+        "git@github.com:kostya-zero/kanri.git"
+    );
+    assert_eq!(
+        options.expand_remote("https://gitlab.com/group/repo"),
+        "https://gitlab.com/group/repo"
+    );
+}
+
+#[test]
+fn test_load_ignore_patterns() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    fs::write(&path, "ignore = [\"tmp-*\", \"!tmp-keep\"]\n").unwrap();
+
+    let config = Config::load(&path).unwrap();
+    assert_eq!(config.ignore, vec!["tmp-*", "!tmp-keep"]);
+
+    fs::write(&path, "ignore = [\"[abc\"]\n").unwrap();
+    assert!(matches!(
+        Config::load(&path),
+        Err(ConfigError::BadConfiguration(_))
+    ));
+}
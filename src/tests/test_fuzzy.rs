@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use crate::fuzzy::{rank, score};
+
+#[test]
+fn test_fuzzy_subsequence() {
+    assert!(score("kn", "kanri").is_some());
+    assert!(score("xqz", "kanri").is_none());
+    assert!(score("KAN", "kanri").is_some());
+}
+
+#[test]
+fn test_fuzzy_prefers_word_boundaries() {
+    let ranked = rank("api", &["rapid", "my-api", "api-gateway"], &HashMap::new());
+    let names: Vec<&str> = ranked.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["api-gateway", "my-api", "rapid"]);
+}
+
+#[test]
+fn test_fuzzy_typo_tolerance() {
+    assert!(score("kanir", "kanri").is_some());
+    assert!(score("bookshlef", "bookshelf").is_some());
+    assert!(score("xyzzy", "kanri").is_none());
+
+    let ranked = rank("wbe", &["web", "wiki"], &HashMap::new());
+    assert_eq!(ranked.len(), 1);
+    assert_eq!(ranked[0].name, "web");
+}
+
+#[test]
+fn test_fuzzy_ranks_subsequence_above_typos() {
+    let ranked = rank("rust", &["rsut", "rust-tools"], &HashMap::new());
+    assert_eq!(ranked[0].name, "rust-tools");
+    assert!(ranked[1].score < ranked[0].score);
+}
+
+#[test]
+fn test_fuzzy_ties_are_deterministic() {
+    let ranked = rank("app", &["app-b", "app-a", "apps"], &HashMap::new());
+    let names: Vec<&str> = ranked.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["apps", "app-a", "app-b"]);
+}
+
+#[test]
+fn test_fuzzy_ties_prefer_weights() {
+    let weights = HashMap::from([(String::from("api-client"), 2.0)]);
+    let ranked = rank("api", &["api-server", "api-client"], &weights);
+    assert_eq!(ranked[0].name, "api-client");
+}
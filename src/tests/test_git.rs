@@ -0,0 +1,31 @@
+use crate::git::GitStatus;
+
+#[test]
+fn test_git_status_parse() {
+    let output = "\
+# branch.oid 5c0ffee
+# branch.head main
+# branch.upstream origin/main
+# branch.ab +2 -1
+# stash 3
+1 .M N... 100644 100644 100644 abc abc src/main.rs
+2 R. N... 100644 100644 100644 abc abc R100 new.rs\told.rs
+? notes.txt
+? scratch/
+";
+    let status = GitStatus::parse(output);
+    assert_eq!(status.branch.as_deref(), Some("main"));
+    assert_eq!(status.ahead, 2);
+    assert_eq!(status.behind, 1);
+    assert_eq!(status.changed, 2);
+    assert_eq!(status.untracked, 2);
+    assert_eq!(status.stashes, 3);
+    assert!(status.is_dirty());
+}
+
+#[test]
+fn test_git_status_parse_clean_detached() {
+    let status = GitStatus::parse("# branch.oid 5c0ffee\n# branch.head (detached)\n");
+    assert_eq!(status.branch, None);
+    assert!(!status.is_dirty());
+}
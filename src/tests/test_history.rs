@@ -0,0 +1,68 @@
+use crate::{history::History, tests::TestContext};
+
+const NOW: u64 = 1_700_000_000;
+
+#[test]
+fn test_history_recent() {
+    let mut history = History::default();
+    history.record("alpha", NOW - 300);
+    history.record("beta", NOW - 200);
+    history.record("alpha", NOW - 100);
+
+    assert_eq!(history.recent(1), Some("alpha"));
+    assert_eq!(history.recent(2), Some("beta"));
+    assert_eq!(history.recent(3), None);
+}
+
+#[test]
+fn test_history_frecency() {
+    let mut history = History::default();
+    // Opened often, but a long time ago.
+    for _ in 0..3 {
+        history.record("old", NOW - 30 * 24 * 60 * 60);
+    }
+    history.record("fresh", NOW - 60);
+
+    let scores = history.scores(NOW);
+    assert!(scores["fresh"] > scores["old"]);
+
+    let entries = history.entries(NOW);
+    assert_eq!(entries[0].project, "fresh");
+    assert_eq!(entries[1].visits, 3);
+}
+
+#[test]
+fn test_history_rename() {
+    let mut history = History::default();
+    history.record("alpha", NOW);
+    history.rename("alpha", "omega");
+
+    assert_eq!(history.recent(1), Some("omega"));
+}
+
+#[test]
+fn test_history_save_and_load() {
+    let context = TestContext::setup();
+    let path = context.path().join("history.json");
+
+    assert!(History::load(&path).unwrap().is_empty());
+
+    let mut history = History::default();
+    history.record("alpha", NOW);
+    history.save(&path).unwrap();
+
+    let loaded = History::load(&path).unwrap();
+    assert_eq!(loaded.recent(1), Some("alpha"));
+}
+
+#[test]
+fn test_history_migrate_recent() {
+    let mut history = History::default();
+    assert!(!history.migrate_recent("", NOW));
+    assert!(history.migrate_recent("alpha", NOW));
+    assert_eq!(history.recent(1), Some("alpha"));
+
+    // Existing history is never overwritten by the legacy field.
+    assert!(!history.migrate_recent("beta", NOW));
+    assert_eq!(history.recent(1), Some("alpha"));
+}
@@ -0,0 +1,39 @@
+use crate::ignore::IgnoreRules;
+
+#[test]
+fn test_ignore_globs_and_comments() {
+    let rules = IgnoreRules::parse("# scratch projects\ntmp-*\n\nold/\n").unwrap();
+
+    assert_eq!(rules.matched("tmp-1"), Some(true));
+    assert_eq!(rules.matched("clients/tmp-2"), Some(true));
+    assert_eq!(rules.matched("old"), Some(true));
+    assert_eq!(rules.matched("# scratch projects"), None);
+    assert_eq!(rules.matched("api"), None);
+}
+
+#[test]
+fn test_ignore_negation() {
+    let rules = IgnoreRules::parse("tmp-*\n!tmp-keep\n").unwrap();
+
+    assert_eq!(rules.matched("tmp-1"), Some(true));
+    assert_eq!(rules.matched("tmp-keep"), Some(false));
+
+    // The last matching pattern wins.
+    let rules = IgnoreRules::parse("!tmp-keep\ntmp-*\n").unwrap();
+    assert_eq!(rules.matched("tmp-keep"), Some(true));
+}
+
+#[test]
+fn test_ignore_anchored() {
+    let rules = IgnoreRules::parse("/build\nclients/*/legacy\n").unwrap();
+
+    assert_eq!(rules.matched("build"), Some(true));
+    assert_eq!(rules.matched("clients/build"), None);
+    assert_eq!(rules.matched("clients/acme/legacy"), Some(true));
+    assert_eq!(rules.matched("clients/acme/team/legacy"), None);
+}
+
+#[test]
+fn test_ignore_bad_pattern() {
+    assert!(IgnoreRules::new(&["[abc"]).is_err());
+}
@@ -1,6 +1,11 @@
 use crate::{
-    library::{CloneOptions, Library, LibraryError},
-    tests::TestContext,
+    commands::root::load_library,
+    config::Config,
+    ignore::IgnoreRules,
+    library::{
+        CloneLayout, CloneOptions, Library, LibraryError, Root, ScanOptions, remote_segments,
+    },
+    tests::{TestContext, single_root},
 };
 use std::{fs, path::PathBuf};
 
@@ -9,13 +14,16 @@ fn test_library_new() {
     let context = TestContext::setup();
     let path = context.path().to_path_buf();
 
-    let library = Library::new(&path, false).unwrap();
+    let library = Library::new(single_root(&path), &ScanOptions::default()).unwrap();
     assert!(library.is_empty());
 
     assert!(context.path().exists());
 
     assert!(matches!(
-        Library::new(&PathBuf::from("/non/existent/path"), false),
+        Library::new(
+            single_root(&PathBuf::from("/non/existent/path")),
+            &ScanOptions::default()
+        ),
         Err(LibraryError::InvalidPath)
     ));
 
@@ -27,9 +35,12 @@ fn test_library_create_project() {
     let context = TestContext::setup();
     let path = context.path().to_path_buf();
 
-    let library = Library::new(&path, false).unwrap();
+    let library = Library::new(single_root(&path), &ScanOptions::default()).unwrap();
 
-    assert!(library.create("new_project").is_ok());
+    assert_eq!(
+        library.create("new_project").unwrap(),
+        context.path().join("new_project")
+    );
     assert!(context.path().join("new_project").exists());
 
     assert!(matches!(
@@ -38,6 +49,20 @@ fn test_library_create_project() {
     ));
 }
 
+#[test]
+fn test_library_delete_only_existing_projects() {
+    let context = TestContext::setup();
+    let library = Library::new(single_root(context.path()), &ScanOptions::default()).unwrap();
+    fs::create_dir(context.path().join("api")).unwrap();
+
+    // Directories that were not in the library when it was loaded are not deleted.
+    assert!(matches!(
+        library.delete("api"),
+        Err(LibraryError::ProjectNotFound)
+    ));
+    assert!(context.path().join("api").exists());
+}
+
 #[test]
 fn test_library_contains() {
     let context = TestContext::setup();
@@ -45,7 +70,7 @@ fn test_library_contains() {
 
     fs::create_dir(context.path().join("test_project")).unwrap();
 
-    let library = Library::new(&path, false).unwrap();
+    let library = Library::new(single_root(&path), &ScanOptions::default()).unwrap();
     assert!(library.contains("test_project"));
     assert!(!library.contains("non_existent_project"));
 }
@@ -57,7 +82,7 @@ fn test_library_get() {
 
     fs::create_dir(context.path().join("test_project")).unwrap();
 
-    let library = Library::new(&path, false).unwrap();
+    let library = Library::new(single_root(&path), &ScanOptions::default()).unwrap();
     assert!(library.get("test_project").is_ok());
     assert!(library.get("non_existent_project").is_err());
 }
@@ -70,11 +95,18 @@ fn test_hidden_projects() {
     fs::create_dir(context.path().join("visible_project")).unwrap();
     fs::create_dir(context.path().join(".hidden_project")).unwrap();
 
-    let library = Library::new(&path, false).unwrap();
+    let library = Library::new(single_root(&path), &ScanOptions::default()).unwrap();
     assert!(library.contains("visible_project"));
     assert!(!library.contains(".hidden_project"));
 
-    let library_with_hidden = Library::new(&path, true).unwrap();
+    let library_with_hidden = Library::new(
+        single_root(&path),
+        &ScanOptions {
+            display_hidden: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
     assert!(library_with_hidden.contains("visible_project"));
     assert!(library_with_hidden.contains(".hidden_project"));
 }
@@ -100,9 +132,373 @@ fn test_clone_options() {
         remote: String::from("https://github.com/user/repo.git"),
         branch: Some(String::from("main")),
         name: Some(String::from("my-repo")),
+        ..Default::default()
     };
 
     assert_eq!(options.remote, "https://github.com/user/repo.git");
     assert_eq!(options.branch, Some(String::from("main")));
     assert_eq!(options.name, Some(String::from("my-repo")));
 }
+
+#[test]
+fn test_library_multiple_roots() {
+    let work = TestContext::setup();
+    let oss = TestContext::setup();
+
+    fs::create_dir(work.path().join("api")).unwrap();
+    fs::create_dir(work.path().join("billing")).unwrap();
+    fs::create_dir(oss.path().join("api")).unwrap();
+
+    let roots = vec![
+        Root::new("work", work.path().to_path_buf()),
+        Root::new("oss", oss.path().to_path_buf()),
+    ];
+    let library = Library::new(roots, &ScanOptions::default()).unwrap();
+
+    assert_eq!(library.get_vec().len(), 3);
+    assert!(library.contains("billing"));
+    assert!(library.contains("work/billing"));
+    assert!(!library.contains("api"));
+
+    let api = library.get("oss/api").unwrap();
+    assert_eq!(api.get_root(), "oss");
+    assert_eq!(api.get_name(), "oss/api");
+    assert_eq!(api.get_path(), oss.path().join("api").to_str().unwrap());
+}
+
+#[test]
+fn test_library_rename_in_root() {
+    let work = TestContext::setup();
+    let oss = TestContext::setup();
+
+    fs::create_dir(work.path().join("api")).unwrap();
+    fs::create_dir(oss.path().join("tool")).unwrap();
+
+    let roots = vec![
+        Root::new("work", work.path().to_path_buf()),
+        Root::new("oss", oss.path().to_path_buf()),
+    ];
+    let library = Library::new(roots, &ScanOptions::default()).unwrap();
+
+    assert!(library.rename("tool", "api").is_ok());
+    assert!(oss.path().join("api").exists());
+    assert!(!work.path().join("tool").exists());
+}
+
+#[test]
+fn test_clone_directory_name() {
+    let options = |remote: &str| CloneOptions {
+        remote: remote.to_string(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        options("https://github.com/kostya-zero/kanri.git").directory_name(),
+        "kanri"
+    );
+    assert_eq!(
+        options("git@github.com:kostya-zero/kanri").directory_name(),
+        "kanri"
+    );
+    assert_eq!(
+        options("https://example.com/repo/").directory_name(),
+        "repo"
+    );
+
+    let named = CloneOptions {
+        name: Some(String::from("tool")),
+        ..options("https://github.com/kostya-zero/kanri.git")
+    };
+    assert_eq!(named.directory_name(), "tool");
+}
+
+#[test]
+fn test_clone_path() {
+    let work = TestContext::setup();
+    let oss = TestContext::setup();
+    let roots = vec![
+        Root::new("work", work.path().to_path_buf()),
+        Root::new("oss", oss.path().to_path_buf()),
+    ];
+    let library = Library::new(roots, &ScanOptions::default()).unwrap();
+    let mut options = CloneOptions {
+        remote: String::from("https://github.com/kostya-zero/kanri.git"),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        library.clone_path(&options).unwrap(),
+        work.path().join("kanri")
+    );
+
+    options.root = Some(String::from("oss"));
+    assert_eq!(
+        library.clone_path(&options).unwrap(),
+        oss.path().join("kanri")
+    );
+
+    options.root = Some(String::from("games"));
+    assert!(matches!(
+        library.clone_path(&options),
+        Err(LibraryError::RootNotFound(_))
+    ));
+}
+
+#[test]
+fn test_clone_path_rejects_names_outside_root() {
+    let context = TestContext::setup();
+    let roots = vec![Root::new("work", context.path().to_path_buf())];
+    let library = Library::new(roots, &ScanOptions::default()).unwrap();
+
+    for name in ["../../escaped", "..", "a/b", "", "/tmp/x"] {
+        let options = CloneOptions {
+            remote: String::from("https://github.com/kostya-zero/kanri.git"),
+            name: Some(String::from(name)),
+            ..Default::default()
+        };
+        assert!(
+            matches!(
+                library.clone_path(&options),
+                Err(LibraryError::InvalidProjectName)
+            ),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn test_remote_segments() {
+    let segments = |remote| remote_segments(remote).map(|s| s.join("/"));
+
+    assert_eq!(
+        segments("https://github.com/kostya-zero/kanri.git").as_deref(),
+        Some("github.com/kostya-zero/kanri")
+    );
+    assert_eq!(
+        segments("ssh://git@gitlab.com:2222/group/sub/repo").as_deref(),
+        Some("gitlab.com/group/sub/repo")
+    );
+    assert_eq!(
+        segments("git@github.com:kostya-zero/kanri.git").as_deref(),
+        Some("github.com/kostya-zero/kanri")
+    );
+    assert_eq!(segments("/srv/git/repo.git"), None);
+    assert_eq!(segments("C:\\repos\\kanri"), None);
+    assert_eq!(segments("https://example.com/repo"), None);
+}
+
+#[test]
+fn test_clone_relative_path() {
+    let options = CloneOptions {
+        remote: String::from("git@github.com:kostya-zero/kanri.git"),
+        layout: CloneLayout::HostOwnerRepo,
+        ..Default::default()
+    };
+    assert_eq!(
+        options.relative_path(),
+        PathBuf::from("github.com/kostya-zero/kanri")
+    );
+
+    let named = CloneOptions {
+        name: Some(String::from("tool")),
+        ..options.clone()
+    };
+    assert_eq!(
+        named.relative_path(),
+        PathBuf::from("github.com/kostya-zero/tool")
+    );
+
+    let local = CloneOptions {
+        remote: String::from("/srv/git/repo.git"),
+        ..options
+    };
+    assert_eq!(local.relative_path(), PathBuf::from("repo"));
+}
+
+#[test]
+fn test_library_nested_host_layout() {
+    let context = TestContext::setup();
+    let repo = context.path().join("github.com/kostya-zero/kanri");
+    fs::create_dir_all(repo.join(".git")).unwrap();
+    fs::create_dir_all(context.path().join("github.com/kostya-zero/notes")).unwrap();
+    fs::create_dir(context.path().join("my.site")).unwrap();
+    fs::create_dir_all(context.path().join("v1.2/docs")).unwrap();
+
+    let options = ScanOptions {
+        host_layout: true,
+        ..Default::default()
+    };
+    let library = Library::new(single_root(context.path()), &options).unwrap();
+    let mut names = library.get_names();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "github.com/kostya-zero/kanri",
+            "github.com/kostya-zero/notes",
+            "my.site",
+            "v1.2"
+        ]
+    );
+    assert_eq!(
+        library
+            .get("github.com/kostya-zero/kanri")
+            .unwrap()
+            .get_path(),
+        repo.to_str().unwrap()
+    );
+
+    // With the flat layout, dotted names are ordinary directories.
+    let library = Library::new(single_root(context.path()), &ScanOptions::default()).unwrap();
+    let mut names = library.get_names();
+    names.sort();
+    assert_eq!(names, vec!["github.com", "my.site", "v1.2"]);
+}
+
+#[test]
+fn test_failed_clone_cleanup() {
+    let context = TestContext::setup();
+    let library = Library::new(single_root(context.path()), &ScanOptions::default()).unwrap();
+    let options = CloneOptions {
+        remote: String::from("https://example.invalid/team/missing.git"),
+        layout: CloneLayout::HostOwnerRepo,
+        quiet: true,
+        depth: Some(1),
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        library.clone(&options),
+        Err(LibraryError::CloneFailed)
+    ));
+    assert!(!context.path().join("example.invalid").exists());
+}
+
+#[test]
+fn test_remove_clone() {
+    let context = TestContext::setup();
+    let repo = context.path().join("github.com/kostya-zero/kanri");
+    fs::create_dir_all(repo.join(".git")).unwrap();
+    fs::create_dir_all(context.path().join("github.com/other/tool")).unwrap();
+
+    let library = Library::new(single_root(context.path()), &ScanOptions::default()).unwrap();
+    library.remove_clone(&repo);
+    assert!(!context.path().join("github.com/kostya-zero").exists());
+    assert!(context.path().join("github.com/other/tool").exists());
+}
+
+#[test]
+fn test_library_nested_groups() {
+    let context = TestContext::setup();
+    let root = context.path();
+    fs::create_dir_all(root.join("clients/acme/api/.git")).unwrap();
+    fs::create_dir_all(root.join("clients/acme/web")).unwrap();
+    fs::write(root.join("clients/acme/web/package.json"), "{}").unwrap();
+    fs::create_dir_all(root.join("clients/globex/site")).unwrap();
+    fs::write(root.join("clients/globex/site/.kanri.toml"), "").unwrap();
+    fs::create_dir_all(root.join("clients/acme/docs")).unwrap();
+    fs::create_dir_all(root.join("notes/drafts")).unwrap();
+    fs::create_dir_all(root.join("deep/a/b/c/.git")).unwrap();
+
+    let options = ScanOptions {
+        max_depth: 3,
+        ..Default::default()
+    };
+    let library = Library::new(single_root(root), &options).unwrap();
+    let mut names = library.get_names();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "clients/acme/api",
+            "clients/acme/docs",
+            "clients/acme/web",
+            "clients/globex/site",
+            "deep/a/b",
+            "notes/drafts"
+        ]
+    );
+
+    // Without grouping, only the top-level directories are projects.
+    let library = Library::new(single_root(root), &ScanOptions::default()).unwrap();
+    let mut names = library.get_names();
+    names.sort();
+    assert_eq!(names, vec!["clients", "deep", "notes"]);
+}
+
+#[test]
+fn test_library_custom_markers() {
+    let context = TestContext::setup();
+    fs::create_dir_all(context.path().join("games/tetris")).unwrap();
+    fs::write(context.path().join("games/tetris/project.godot"), "").unwrap();
+
+    let options = ScanOptions {
+        max_depth: 2,
+        markers: vec![String::from("project.godot")],
+        ..Default::default()
+    };
+    let library = Library::new(single_root(context.path()), &options).unwrap();
+    assert_eq!(library.get_names(), vec!["games/tetris"]);
+}
+
+#[test]
+fn test_library_ignore_patterns() {
+    let context = TestContext::setup();
+    let root = context.path();
+    for name in ["api", "tmp-1", "tmp-keep", "old"] {
+        fs::create_dir(root.join(name)).unwrap();
+    }
+    fs::write(root.join(".ignore"), "# scratch\ntmp-*\n!tmp-keep\n").unwrap();
+
+    let options = ScanOptions {
+        ignore: IgnoreRules::new(&["old/"]).unwrap(),
+        ..Default::default()
+    };
+    let library = Library::new(single_root(root), &options).unwrap();
+    let mut names = library.get_names();
+    names.sort();
+    assert_eq!(names, vec!["api", "tmp-keep"]);
+}
+
+#[test]
+fn test_library_nested_ignore_files() {
+    let context = TestContext::setup();
+    let root = context.path();
+    fs::create_dir_all(root.join("clients/acme/api/.git")).unwrap();
+    fs::create_dir_all(root.join("clients/acme/legacy/.git")).unwrap();
+    fs::create_dir_all(root.join("clients/globex/site/.git")).unwrap();
+    fs::create_dir_all(root.join("clients/initech/app/.git")).unwrap();
+    fs::write(root.join(".ignore"), "legacy\n/clients/initech\n").unwrap();
+    // A closer `.ignore` file overrides the one in the root.
+    fs::write(root.join("clients/acme/.ignore"), "!legacy\n").unwrap();
+    fs::write(root.join("clients/globex/.ignore"), "site\n").unwrap();
+
+    let options = ScanOptions {
+        max_depth: 3,
+        ..Default::default()
+    };
+    let library = Library::new(single_root(root), &options).unwrap();
+    let mut names = library.get_names();
+    names.sort();
+    // A group whose projects are all ignored is listed itself, like in the root.
+    assert_eq!(
+        names,
+        vec!["clients/acme/api", "clients/acme/legacy", "clients/globex"]
+    );
+}
+
+#[test]
+fn test_load_library_skips_missing_roots() {
+    let context = TestContext::setup();
+    fs::create_dir(context.path().join("api")).unwrap();
+    let mut config = Config::default();
+    config.options.projects_directory = context.path().to_path_buf();
+    config
+        .options
+        .roots
+        .insert(String::from("old"), context.path().join("missing"));
+
+    let library = load_library(&config, None).unwrap();
+    assert_eq!(library.get_names(), vec!["api"]);
+    assert!(load_library(&config, Some("old")).is_err());
+}
@@ -0,0 +1,56 @@
+use crate::manifest::{Manifest, ManifestEntry};
+
+#[test]
+fn test_manifest_parse() {
+    let manifest = Manifest::parse(
+        r#"
+[[repositories]]
+remote = "https://github.com/team/api.git"
+branch = "develop"
+root = "work"
+tags = ["backend"]
+
+[[repositories]]
+remote = "git@github.com:team/web.git"
+name = "frontend"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(manifest.repositories.len(), 2);
+    let api = manifest.repositories[0].clone_options();
+    assert_eq!(api.directory_name(), "api");
+    assert_eq!(api.branch.as_deref(), Some("develop"));
+    assert_eq!(api.root.as_deref(), Some("work"));
+    assert!(api.quiet);
+    assert_eq!(
+        manifest.repositories[1].clone_options().directory_name(),
+        "frontend"
+    );
+}
+
+#[test]
+fn test_manifest_round_trip() {
+    let manifest = Manifest {
+        repositories: vec![ManifestEntry {
+            remote: String::from("https://github.com/team/api.git"),
+            tags: vec![String::from("backend")],
+            ..Default::default()
+        }],
+    };
+    let content = manifest.to_toml().unwrap();
+    assert!(!content.contains("branch"));
+    assert_eq!(Manifest::parse(&content).unwrap(), manifest);
+}
+
+#[test]
+fn test_manifest_rejects_unknown_fields() {
+    assert!(Manifest::parse("[[repositories]]\nremote = \"a\"\nurl = \"b\"\n").is_err());
+}
+
+#[test]
+fn test_manifest_rejects_names_outside_root() {
+    let content =
+        "[[repositories]]\nremote = \"https://github.com/team/api.git\"\nname = \"../../x\"\n";
+    assert!(Manifest::parse(content).is_err());
+}
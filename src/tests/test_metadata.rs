@@ -0,0 +1,107 @@
+use std::fs;
+
+use crate::{
+    metadata::{LOCAL_METADATA_FILE, MetadataStore, ProjectMetadata},
+    tests::TestContext,
+};
+
+#[test]
+fn test_metadata_central_store() {
+    let context = TestContext::setup();
+    let project = context.path().join("bookshelf");
+    let store_path = context.path().join("metadata.json");
+    fs::create_dir(&project).unwrap();
+
+    let mut store = MetadataStore::load(&store_path).unwrap();
+    assert_eq!(store.get(&project).unwrap(), ProjectMetadata::default());
+
+    store
+        .update(&project, |metadata| {
+            assert!(metadata.add_tag("rust"));
+            assert!(metadata.add_tag("cli"));
+            assert!(!metadata.add_tag("rust"));
+            metadata.description = String::from("Keeps track of books");
+            metadata.pinned = true;
+        })
+        .unwrap();
+    store.save(&store_path).unwrap();
+
+    let store = MetadataStore::load(&store_path).unwrap();
+    let metadata = store.get(&project).unwrap();
+    assert_eq!(metadata.tags, vec!["cli", "rust"]);
+    assert_eq!(metadata.description, "Keeps track of books");
+    assert!(metadata.pinned);
+    assert!(!project.join(LOCAL_METADATA_FILE).exists());
+}
+
+#[test]
+fn test_metadata_local_file() {
+    let context = TestContext::setup();
+    let project = context.path().join("bookshelf");
+    fs::create_dir(&project).unwrap();
+    let local_path = project.join(LOCAL_METADATA_FILE);
+    fs::write(
+        &local_path,
+        "# Shared with the team\ndescription = \"Books\"\ntags = [\"web\"]\n",
+    )
+    .unwrap();
+
+    let mut store = MetadataStore::default();
+    let metadata = store.get(&project).unwrap();
+    assert_eq!(metadata.description, "Books");
+    assert!(metadata.has_tag("web"));
+
+    // Opening a project does not touch the local file.
+    store
+        .update(&project, |metadata| metadata.last_opened = Some(100))
+        .unwrap();
+    assert!(
+        fs::read_to_string(&local_path)
+            .unwrap()
+            .starts_with("# Shared")
+    );
+    assert_eq!(store.get(&project).unwrap().last_opened, Some(100));
+
+    store
+        .update(&project, |metadata| {
+            assert!(metadata.remove_tag("web"));
+            metadata.pinned = true;
+        })
+        .unwrap();
+    let content = fs::read_to_string(&local_path).unwrap();
+    assert!(content.contains("pinned = true"));
+    assert!(!content.contains("last_opened"));
+}
+
+#[test]
+fn test_metadata_rename() {
+    let context = TestContext::setup();
+    let old_path = context.path().join("old");
+    let new_path = context.path().join("new");
+    fs::create_dir(&old_path).unwrap();
+
+    let mut store = MetadataStore::default();
+    store
+        .update(&old_path, |metadata| metadata.pinned = true)
+        .unwrap();
+    store.rename(&old_path, &new_path);
+
+    assert!(store.get(&new_path).unwrap().pinned);
+    assert!(!store.get(&old_path).unwrap().pinned);
+}
+
+#[test]
+fn test_metadata_broken_local_file() {
+    let context = TestContext::setup();
+    let project = context.path().join("bookshelf");
+    fs::create_dir(&project).unwrap();
+    fs::write(project.join(LOCAL_METADATA_FILE), "tags = [unclosed").unwrap();
+
+    let mut store = MetadataStore::default();
+    assert!(store.get(&project).is_err());
+
+    // Opening a project only touches the central file, and only when the time changes.
+    assert!(store.set_last_opened(&project, 100));
+    assert!(!store.set_last_opened(&project, 100));
+    assert_eq!(store.get_central(&project).last_opened, Some(100));
+}
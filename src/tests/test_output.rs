@@ -0,0 +1,26 @@
+use crate::{git::GitStatus, output::GitInfo};
+
+#[test]
+fn test_git_info_schema() {
+    let status = GitStatus {
+        branch: Some(String::from("main")),
+        ahead: 1,
+        behind: 0,
+        changed: 0,
+        untracked: 2,
+        stashes: 3,
+    };
+    let value = serde_json::to_value(GitInfo::from(&status)).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "branch": "main",
+            "dirty": true,
+            "ahead": 1,
+            "behind": 0,
+            "changed": 0,
+            "untracked": 2,
+            "stashes": 3,
+        })
+    );
+}
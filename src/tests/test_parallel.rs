@@ -0,0 +1,29 @@
+use std::{thread, time::Duration};
+
+use crate::parallel;
+
+#[test]
+fn test_parallel_map_keeps_order() {
+    let items: Vec<u64> = (0..20).collect();
+    let results = parallel::map(&items, 4, |item| {
+        thread::sleep(Duration::from_millis(20 - item));
+        item * 2
+    });
+    assert_eq!(results, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_parallel_map_empty() {
+    let items: Vec<u64> = Vec::new();
+    assert!(parallel::map(&items, 0, |item| *item).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "job 3 failed")]
+fn test_parallel_map_propagates_panics() {
+    let items: Vec<u64> = (0..8).collect();
+    parallel::map(&items, 4, |item| {
+        assert_ne!(*item, 3, "job 3 failed");
+        *item
+    });
+}
@@ -0,0 +1,105 @@
+// Checks processes through `/proc`, which only Linux has.
+#![cfg(target_os = "linux")]
+
+use std::{
+    env, fs,
+    io::Write,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use crate::{
+    program::{LaunchOptions, ProgramError, launch_captured, launch_program},
+    tests::TestContext,
+};
+
+#[test]
+fn test_timeout_kills_grandchildren() {
+    let context = TestContext::setup();
+    let pid_file = context.path().join("pid");
+    let options = LaunchOptions {
+        program: String::from("sh"),
+        args: vec![
+            String::from("-c"),
+            format!("sleep 30 & echo $! > {}; wait", pid_file.display()),
+        ],
+        quiet: true,
+        timeout: Some(Duration::from_millis(500)),
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        launch_program(options),
+        Err(ProgramError::TimedOut(_))
+    ));
+
+    // A killed process that was not reaped yet is a zombie, marked `Z` in its status.
+    let pid = fs::read_to_string(&pid_file).unwrap();
+    let status = fs::read_to_string(format!("/proc/{}/status", pid.trim())).unwrap_or_default();
+    assert!(
+        status.is_empty() || status.contains("State:\tZ"),
+        "the sleep started by the shell is still running"
+    );
+}
+
+/// Runs a step that reads from the terminal. Started by [`test_timeout_step_reads_terminal`],
+/// because the test needs a terminal of its own.
+#[test]
+#[ignore = "needs a terminal, see test_timeout_step_reads_terminal"]
+fn timeout_step_in_terminal() {
+    let options = LaunchOptions {
+        program: String::from("sh"),
+        args: vec![
+            String::from("-c"),
+            String::from("read answer && [ \"$answer\" = hello ]"),
+        ],
+        timeout: Some(Duration::from_secs(10)),
+        ..Default::default()
+    };
+    launch_program(options).unwrap();
+}
+
+#[test]
+fn test_timeout_step_reads_terminal() {
+    // `script` runs the command in a new terminal, which gets what is written to its stdin.
+    let command = format!(
+        "{} --exact --ignored tests::test_program::timeout_step_in_terminal",
+        env::current_exe().unwrap().display()
+    );
+    let Ok(mut child) = Command::new("script")
+        .args(["-qec", &command, "/dev/null"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+    else {
+        // `script` is a part of util-linux, which may be missing.
+        return;
+    };
+    child.stdin.take().unwrap().write_all(b"hello\n").unwrap();
+
+    // A step in the background would be stopped by the read until it times out.
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_launch_captured_reports_reason() {
+    let shell = |script: &str| LaunchOptions {
+        program: String::from("sh"),
+        args: vec![String::from("-c"), script.to_string()],
+        ..Default::default()
+    };
+
+    let result = launch_captured(shell(
+        "echo hint >&2; echo 'fatal: no upstream' >&2; echo 'fatal: aborting' >&2; exit 1",
+    ));
+    assert!(matches!(result, Err(ProgramError::Failed(reason)) if reason == "fatal: no upstream"));
+
+    let result = launch_captured(shell("echo 'could not lock' >&2; exit 1"));
+    assert!(matches!(result, Err(ProgramError::Failed(reason)) if reason == "could not lock"));
+
+    assert!(matches!(
+        launch_captured(shell("exit 3")),
+        Err(ProgramError::NonZeroExitCode(3))
+    ));
+    assert!(launch_captured(shell("true")).is_ok());
+}
@@ -1,5 +1,6 @@
 use std::fs;
 
+use detect::{BuildSystem, Language, ProjectType};
 use library::Project;
 
 use super::*;
@@ -41,3 +42,48 @@ fn test_project_is_empty() {
 
     assert!(context.path().exists());
 }
+
+#[test]
+fn test_project_size_and_modified() {
+    let context = TestContext::setup();
+    let project = Project::new("test_project", context.path().to_path_buf());
+    assert_eq!(project.size(), 0);
+
+    fs::create_dir(context.path().join("src")).unwrap();
+    fs::write(context.path().join("src/main.rs"), "fn main() {}").unwrap();
+    fs::write(context.path().join("README.md"), "# Test").unwrap();
+    assert_eq!(project.size(), 18);
+    assert!(project.modified().is_some());
+}
+
+#[test]
+fn test_project_type() {
+    let context = TestContext::setup();
+    assert_eq!(ProjectType::detect(context.path()), ProjectType::default());
+
+    fs::write(context.path().join("App.csproj"), "").unwrap();
+    fs::write(context.path().join("package.json"), "{}").unwrap();
+    fs::write(context.path().join("pnpm-lock.yaml"), "").unwrap();
+    fs::write(context.path().join("Cargo.toml"), "").unwrap();
+    let project_type = ProjectType::detect(context.path());
+    assert_eq!(
+        project_type.languages,
+        vec![Language::Rust, Language::JavaScript, Language::CSharp]
+    );
+    assert_eq!(
+        project_type.build_systems,
+        vec![BuildSystem::Cargo, BuildSystem::Pnpm, BuildSystem::MSBuild]
+    );
+    assert_eq!(project_type.language(), Some(Language::Rust));
+}
+
+#[test]
+fn test_project_type_is_cached() {
+    let context = TestContext::setup();
+    let project = Project::new("test_project", context.path().to_path_buf());
+    fs::write(context.path().join("go.mod"), "").unwrap();
+    assert!(project.project_type().has_language(Language::Go));
+
+    fs::remove_file(context.path().join("go.mod")).unwrap();
+    assert!(project.project_type().has_language(Language::Go));
+}
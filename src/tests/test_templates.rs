@@ -0,0 +1,346 @@
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+
+use crate::{
+    config::ShellOptions,
+    templates::{
+        TemplateKind, TemplateStep, TemplateVariable, Templates, TemplatesError, render,
+        render_tree,
+    },
+    tests::TestContext,
+};
+
+const TEMPLATES_JSON: &str = r#"{
+    "legacy": ["cargo init"],
+    "rust": {
+        "variables": [
+            { "name": "license", "description": "License", "default": "MIT", "choices": ["MIT", "Apache-2.0"] },
+            { "name": "version", "pattern": "[0-9]+\\.[0-9]+" }
+        ],
+        "commands": ["cargo init --name {{project}}", "echo {{ license }} > LICENSE"]
+    },
+    "web": { "kind": "skeleton" },
+    "docs": {
+        "commands": [
+            "mdbook init",
+            {
+                "command": "npm install",
+                "label": "Installing dependencies of {{project}}",
+                "cwd": "site",
+                "env": { "NODE_ENV": "development", "SITE": "{{project}}" },
+                "timeout": 60,
+                "continue_on_error": true,
+                "shell": "sh"
+            }
+        ]
+    }
+}"#;
+
+fn load_templates() -> Templates {
+    let context = TestContext::setup();
+    let path = context.path().join("templates.json");
+    fs::write(&path, TEMPLATES_JSON).unwrap();
+    Templates::load(&path).unwrap()
+}
+
+#[test]
+fn test_templates_load_both_forms() {
+    let templates = load_templates();
+
+    let legacy = templates.get_template("legacy").unwrap();
+    assert!(legacy.variables.is_empty());
+    assert_eq!(legacy.commands, vec![TemplateStep::from("cargo init")]);
+
+    let rust = templates.get_template("rust").unwrap();
+    assert_eq!(rust.kind, TemplateKind::Commands);
+    assert_eq!(rust.variables.len(), 2);
+    assert_eq!(rust.commands.len(), 2);
+
+    let web = templates.get_template("web").unwrap();
+    assert_eq!(web.kind, TemplateKind::Skeleton);
+    assert!(web.commands.is_empty());
+}
+
+#[test]
+fn test_templates_reject_unknown_fields() {
+    let context = TestContext::setup();
+    let path = context.path().join("templates.json");
+    fs::write(&path, r#"{ "rust": { "comands": ["cargo init"] } }"#).unwrap();
+
+    assert!(matches!(
+        Templates::load(&path),
+        Err(TemplatesError::DeserializationError)
+    ));
+}
+
+#[test]
+fn test_templates_resolve_variables() {
+    let templates = load_templates();
+    let template = templates.get_template("rust").unwrap();
+
+    let provided = HashMap::from([(String::from("version"), String::from("1.2"))]);
+    let values = template.resolve_variables(&provided, |_| None).unwrap();
+    assert_eq!(values["license"], "MIT");
+    assert_eq!(values["version"], "1.2");
+
+    // Answers are validated as well.
+    assert!(
+        template
+            .resolve_variables(&provided, |_| Some(String::from("GPL")))
+            .is_err()
+    );
+
+    let values = template
+        .resolve_variables(&provided, |_| Some(String::from("Apache-2.0")))
+        .unwrap();
+    assert_eq!(values["license"], "Apache-2.0");
+}
+
+#[test]
+fn test_templates_resolve_errors() {
+    let templates = load_templates();
+    let template = templates.get_template("rust").unwrap();
+
+    assert!(matches!(
+        template.resolve_variables(&HashMap::new(), |_| None),
+        Err(TemplatesError::MissingVariable(name)) if name == "version"
+    ));
+
+    let provided = HashMap::from([(String::from("version"), String::from("one"))]);
+    assert!(matches!(
+        template.resolve_variables(&provided, |_| None),
+        Err(TemplatesError::InvalidValue(..))
+    ));
+
+    let provided = HashMap::from([(String::from("unknown"), String::from("1"))]);
+    assert!(matches!(
+        template.resolve_variables(&provided, |_| None),
+        Err(TemplatesError::UnknownVariable(name)) if name == "unknown"
+    ));
+}
+
+#[test]
+fn test_templates_variable_validation() {
+    let variable = TemplateVariable {
+        name: String::from("version"),
+        pattern: Some(String::from("[0-9]+")),
+        ..Default::default()
+    };
+    assert!(variable.validate("42").is_ok());
+    assert!(variable.validate("42a").is_err());
+}
+
+#[test]
+fn test_templates_render() {
+    let values = HashMap::from([
+        (String::from("project"), String::from("bookshelf")),
+        (String::from("license"), String::from("MIT")),
+    ]);
+
+    assert_eq!(
+        render("init {{project}} --license {{ license }}", &values).unwrap(),
+        "init bookshelf --license MIT"
+    );
+    assert_eq!(render("no variables", &values).unwrap(), "no variables");
+    assert!(render("{{missing}}", &values).is_err());
+}
+
+#[test]
+fn test_templates_render_tree() {
+    let skeleton = TestContext::setup();
+    let target = TestContext::setup();
+    let values = HashMap::from([(String::from("project"), String::from("bookshelf"))]);
+
+    fs::create_dir(skeleton.path().join("src")).unwrap();
+    fs::write(skeleton.path().join("README.md"), "# {{ project }}").unwrap();
+    fs::write(
+        skeleton.path().join("App.vue"),
+        "<p>{{ message }} from {{project}}</p>",
+    )
+    .unwrap();
+    fs::write(skeleton.path().join("{{ layout }}.html"), "").unwrap();
+    fs::write(
+        skeleton.path().join("src/{{project}}.rs"),
+        "// ${{ github.ref }}",
+    )
+    .unwrap();
+    fs::write(skeleton.path().join("logo.bin"), [0xff, 0xfe, 0x7b, 0x7b]).unwrap();
+
+    let project = target.path().join("bookshelf");
+    render_tree(skeleton.path(), &project, &values).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(project.join("README.md")).unwrap(),
+        "# bookshelf"
+    );
+    // Placeholders of other template engines are not variables of the template.
+    assert_eq!(
+        fs::read_to_string(project.join("App.vue")).unwrap(),
+        "<p>{{ message }} from bookshelf</p>"
+    );
+    assert!(project.join("{{ layout }}.html").exists());
+    assert_eq!(
+        fs::read_to_string(project.join("src/bookshelf.rs")).unwrap(),
+        "// ${{ github.ref }}"
+    );
+    assert_eq!(
+        fs::read(project.join("logo.bin")).unwrap(),
+        vec![0xff, 0xfe, 0x7b, 0x7b]
+    );
+}
+
+#[test]
+fn test_templates_render_tree_rejects_unsafe_names() {
+    let skeleton = TestContext::setup();
+    let target = TestContext::setup();
+    fs::write(skeleton.path().join("{{name}}"), "").unwrap();
+    let project = target.path().join("bookshelf");
+
+    for name in ["../escape", "a/b", ".."] {
+        let values = HashMap::from([(String::from("name"), name.to_string())]);
+        assert!(matches!(
+            render_tree(skeleton.path(), &project, &values),
+            Err(TemplatesError::InvalidFileName(_))
+        ));
+    }
+    assert!(!target.path().join("escape").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_templates_render_tree_symlinks() {
+    let skeleton = TestContext::setup();
+    let target = TestContext::setup();
+    fs::create_dir(skeleton.path().join("shared")).unwrap();
+    fs::write(skeleton.path().join("shared/notes.md"), "notes").unwrap();
+    std::os::unix::fs::symlink("shared", skeleton.path().join("docs")).unwrap();
+
+    let project = target.path().join("bookshelf");
+    render_tree(skeleton.path(), &project, &HashMap::new()).unwrap();
+
+    assert!(project.join("docs").is_symlink());
+    assert_eq!(
+        fs::read_to_string(project.join("docs/notes.md")).unwrap(),
+        "notes"
+    );
+}
+
+#[test]
+fn test_templates_plan() {
+    let templates = load_templates();
+    let template = templates.get_template("rust").unwrap();
+    let shell = ShellOptions {
+        program: String::from("bash"),
+        args: vec![String::from("-c")],
+    };
+    let values = HashMap::from([
+        (String::from("license"), String::from("MIT")),
+        (String::from("version"), String::from("1.0")),
+    ]);
+    let project_path = PathBuf::from("/projects/bookshelf");
+
+    let plan = template
+        .plan("rust", "bookshelf", &project_path, &values, &shell)
+        .unwrap();
+
+    assert_eq!(plan.project_path, project_path);
+    assert!(plan.skeleton.is_none());
+    assert_eq!(plan.steps.len(), 2);
+
+    let step = &plan.steps[0];
+    assert_eq!(step.label, "cargo init --name bookshelf");
+    assert_eq!(step.program, "bash");
+    assert_eq!(step.args, vec!["-c", "cargo init --name bookshelf"]);
+    assert_eq!(step.cwd, project_path);
+    assert_eq!(
+        step.env,
+        vec![
+            (String::from("ENJO_PROJECT"), String::from("bookshelf")),
+            (String::from("KANRI_VAR_LICENSE"), String::from("MIT")),
+            (String::from("KANRI_VAR_PROJECT"), String::from("bookshelf")),
+            (String::from("KANRI_VAR_VERSION"), String::from("1.0")),
+        ]
+    );
+    assert_eq!(plan.steps[1].label, "echo MIT > LICENSE");
+
+    let no_shell = ShellOptions {
+        program: String::new(),
+        args: Vec::new(),
+    };
+    assert!(matches!(
+        template.plan("rust", "bookshelf", &project_path, &values, &no_shell),
+        Err(TemplatesError::ShellNotConfigured)
+    ));
+}
+
+#[test]
+fn test_templates_structured_steps() {
+    let templates = load_templates();
+    let template = templates.get_template("docs").unwrap();
+    assert_eq!(template.commands[0], TemplateStep::from("mdbook init"));
+    assert_eq!(template.commands[1].timeout, Some(60));
+
+    let shell = ShellOptions {
+        program: String::from("bash"),
+        args: vec![String::from("-c")],
+    };
+    let project_path = PathBuf::from("/projects/book");
+    let plan = template
+        .plan("docs", "book", &project_path, &HashMap::new(), &shell)
+        .unwrap();
+
+    let step = &plan.steps[1];
+    assert_eq!(step.label, "Installing dependencies of book");
+    assert_eq!(step.program, "sh");
+    assert_eq!(step.args, vec!["-c", "npm install"]);
+    assert_eq!(step.cwd, project_path.join("site"));
+    assert_eq!(step.timeout, Some(Duration::from_secs(60)));
+    assert!(step.continue_on_error);
+    assert!(
+        step.env
+            .contains(&(String::from("SITE"), String::from("book")))
+    );
+    assert!(!plan.steps[0].continue_on_error);
+}
+
+#[test]
+fn test_templates_steps_round_trip() {
+    let context = TestContext::setup();
+    let path = context.path().join("templates.json");
+    fs::write(&path, TEMPLATES_JSON).unwrap();
+    let templates = Templates::load(&path).unwrap();
+    templates.save(&path).unwrap();
+
+    // Plain commands are still stored as strings.
+    let saved: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["docs"]["commands"][0], "mdbook init");
+    assert_eq!(saved["docs"]["commands"][1]["cwd"], "site");
+
+    let reloaded = Templates::load(&path).unwrap();
+    assert_eq!(
+        reloaded.get_template("docs").unwrap().commands,
+        templates.get_template("docs").unwrap().commands
+    );
+}
+
+#[test]
+fn test_templates_step_directory_must_be_relative() {
+    let shell = ShellOptions {
+        program: String::from("bash"),
+        args: vec![String::from("-c")],
+    };
+    let project_path = PathBuf::from("/projects/book");
+
+    for dir in ["../outside", "/tmp"] {
+        let mut templates = Templates::new();
+        templates
+            .add_template("escape", vec![String::from("ls")])
+            .unwrap();
+        let mut template = templates.get_template("escape").unwrap().clone();
+        template.commands[0].cwd = Some(dir.to_string());
+        assert!(matches!(
+            template.plan("escape", "book", &project_path, &HashMap::new(), &shell),
+            Err(TemplatesError::InvalidStepDirectory(_))
+        ));
+    }
+}
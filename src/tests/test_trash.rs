@@ -0,0 +1,81 @@
+use std::fs;
+
+use crate::{
+    library::{Library, ScanOptions},
+    tests::{TestContext, single_root},
+    trash::{Trash, TrashError},
+};
+
+#[test]
+fn test_trash_move_and_restore() {
+    let projects = TestContext::setup();
+    let config = TestContext::setup();
+    let project_path = projects.path().join("bookshelf");
+    fs::create_dir_all(project_path.join("src")).unwrap();
+    fs::write(project_path.join("src/main.rs"), "fn main() {}").unwrap();
+
+    let library = Library::new(single_root(projects.path()), &ScanOptions::default()).unwrap();
+    let trash = Trash::new(config.path().join("trash"));
+    let entry = library.move_to_trash("bookshelf", &trash, 100).unwrap();
+
+    assert!(!project_path.exists());
+    assert_eq!(entry.name, "bookshelf");
+    assert_eq!(entry.root, "default");
+    assert_eq!(entry.path, project_path);
+
+    let entries = trash.entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].deleted_at, 100);
+    assert_eq!(entries[0].id, entry.id);
+
+    let found = trash.find("default/bookshelf").unwrap();
+    trash.restore(&found).unwrap();
+    assert_eq!(
+        fs::read_to_string(project_path.join("src/main.rs")).unwrap(),
+        "fn main() {}"
+    );
+    assert!(trash.entries().unwrap().is_empty());
+}
+
+#[test]
+fn test_trash_restore_does_not_overwrite() {
+    let projects = TestContext::setup();
+    let config = TestContext::setup();
+    let project_path = projects.path().join("bookshelf");
+    fs::create_dir(&project_path).unwrap();
+
+    let trash = Trash::new(config.path().join("trash"));
+    let entry = trash.put("default", &project_path, 100).unwrap();
+    fs::create_dir(&project_path).unwrap();
+
+    assert!(matches!(
+        trash.restore(&entry),
+        Err(TrashError::AlreadyExists(_))
+    ));
+    assert!(matches!(
+        trash.find("unknown"),
+        Err(TrashError::EntryNotFound(_))
+    ));
+}
+
+#[test]
+fn test_trash_keeps_projects_with_same_name() {
+    let projects = TestContext::setup();
+    let config = TestContext::setup();
+    let project_path = projects.path().join("bookshelf");
+    let trash = Trash::new(config.path().join("trash"));
+
+    fs::create_dir(&project_path).unwrap();
+    trash.put("default", &project_path, 100).unwrap();
+    fs::create_dir(&project_path).unwrap();
+    let newest = trash.put("default", &project_path, 200).unwrap();
+
+    let entries = trash.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(trash.find("bookshelf").unwrap().id, newest.id);
+
+    trash.purge(&entries[1]).unwrap();
+    let entries = trash.entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].deleted_at, 200);
+}
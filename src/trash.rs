@@ -0,0 +1,205 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Name of the file with metadata inside of a trash entry.
+const ENTRY_FILE: &str = "entry.json";
+
+/// Name of the directory with files of the project inside of a trash entry.
+const CONTENT_DIR: &str = "project";
+
+#[derive(Debug, Error)]
+pub enum TrashError {
+    #[error("Failed to read the trash.")]
+    ReadFailed,
+
+    #[error("Failed to write the trash.")]
+    WriteFailed,
+
+    #[error("Failed to move files: {0}.")]
+    MoveFailed(String),
+
+    #[error("Project '{0}' not found in the trash.")]
+    EntryNotFound(String),
+
+    #[error("Path '{0}' already exists.")]
+    AlreadyExists(String),
+}
+
+/// A removed project. Each entry is a directory in the trash with the files of the
+/// project and a file with this metadata.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrashEntry {
+    /// Name of the entry directory in the trash.
+    #[serde(skip)]
+    pub id: String,
+
+    /// Name of the project directory.
+    pub name: String,
+
+    /// Name of the root the project was in.
+    pub root: String,
+
+    /// Where the project was before it was removed.
+    pub path: PathBuf,
+
+    /// Time of removal as seconds since the Unix epoch.
+    pub deleted_at: u64,
+}
+
+impl TrashEntry {
+    /// Name of the project prefixed with the name of its root, e.g. `work/api`.
+    pub fn get_qualified_name(&self) -> String {
+        format!("{}/{}", self.root, self.name)
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.name == name || self.id == name || self.get_qualified_name() == name
+    }
+}
+
+pub struct Trash {
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Moves the project at `path` into the trash.
+    pub fn put(&self, root: &str, path: &Path, deleted_at: u64) -> Result<TrashEntry, TrashError> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| TrashError::MoveFailed(path.display().to_string()))?;
+
+        let mut id = format!("{deleted_at}-{name}");
+        let mut suffix = 1;
+        while self.dir.join(&id).exists() {
+            suffix += 1;
+            id = format!("{deleted_at}-{name}-{suffix}");
+        }
+
+        let entry = TrashEntry {
+            id,
+            name,
+            root: root.to_string(),
+            path: path.to_path_buf(),
+            deleted_at,
+        };
+
+        let entry_dir = self.dir.join(&entry.id);
+        fs::create_dir_all(&entry_dir).map_err(|_| TrashError::WriteFailed)?;
+        let content = serde_json::to_string(&entry).map_err(|_| TrashError::WriteFailed)?;
+        fs::write(entry_dir.join(ENTRY_FILE), content).map_err(|_| TrashError::WriteFailed)?;
+
+        if let Err(e) = move_dir(path, &entry_dir.join(CONTENT_DIR)) {
+            let _ = fs::remove_dir_all(&entry_dir);
+            return Err(e);
+        }
+        Ok(entry)
+    }
+
+    /// All entries in the trash, the most recently removed first.
+    /// Directories without valid metadata are skipped.
+    pub fn entries(&self) -> Result<Vec<TrashEntry>, TrashError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for dir_entry in fs::read_dir(&self.dir).map_err(|_| TrashError::ReadFailed)? {
+            let dir_entry = dir_entry.map_err(|_| TrashError::ReadFailed)?;
+            let Ok(content) = fs::read_to_string(dir_entry.path().join(ENTRY_FILE)) else {
+                continue;
+            };
+            let Ok(mut entry) = serde_json::from_str::<TrashEntry>(&content) else {
+                continue;
+            };
+            entry.id = dir_entry.file_name().to_string_lossy().to_string();
+            entries.push(entry);
+        }
+
+        entries.sort_by(|a, b| {
+            b.deleted_at
+                .cmp(&a.deleted_at)
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        Ok(entries)
+    }
+
+    /// Finds the most recently removed project with the given name, qualified name or id.
+    pub fn find(&self, name: &str) -> Result<TrashEntry, TrashError> {
+        self.entries()?
+            .into_iter()
+            .find(|entry| entry.matches(name))
+            .ok_or_else(|| TrashError::EntryNotFound(name.to_string()))
+    }
+
+    /// Moves the project back to the place it was removed from.
+    pub fn restore(&self, entry: &TrashEntry) -> Result<(), TrashError> {
+        if entry.path.exists() {
+            return Err(TrashError::AlreadyExists(entry.path.display().to_string()));
+        }
+        if let Some(parent) = entry.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| TrashError::MoveFailed(e.to_string()))?;
+        }
+
+        let entry_dir = self.dir.join(&entry.id);
+        move_dir(&entry_dir.join(CONTENT_DIR), &entry.path)?;
+        fs::remove_dir_all(entry_dir).map_err(|_| TrashError::WriteFailed)
+    }
+
+    /// Deletes the entry permanently.
+    pub fn purge(&self, entry: &TrashEntry) -> Result<(), TrashError> {
+        fs::remove_dir_all(self.dir.join(&entry.id)).map_err(|_| TrashError::WriteFailed)
+    }
+}
+
+/// Moves a directory. Falls back to copying when renaming is not possible,
+/// e.g. when the trash is on another file system.
+fn move_dir(source: &Path, target: &Path) -> Result<(), TrashError> {
+    if fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+
+    if let Err(e) = copy_dir(source, target) {
+        let _ = fs::remove_dir_all(target);
+        return Err(TrashError::MoveFailed(e.to_string()));
+    }
+    fs::remove_dir_all(source).map_err(|e| TrashError::MoveFailed(e.to_string()))
+}
+
+fn copy_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let source_path = entry.path();
+        let target_path = target.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            copy_dir(&source_path, &target_path)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&source_path, &target_path)?;
+        } else {
+            fs::copy(&source_path, &target_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::copy(source, target).map(|_| ())
+}